@@ -1,87 +1,96 @@
+use advent_of_code::scanner::Scanner;
+
 advent_of_code::solution!(1);
 
-#[derive(Clone)]
-struct Digit {
-    text: String,
-    value: u32,
-}
+/// The spelled-out digits of part two, the puzzle does not count `zero`.
+const WORDS: [&str; 10] = [
+    "", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
 
-impl Digit {
-    fn new(text: String, value: u32) -> Self {
-        Self { text, value }
-    }
+/// The digits `1` to `9` and every word of `languages` with its position as value.
+///
+/// Words are comma separated and start at zero, e.g. `zero` adds just `zero` and
+/// `,eins,zwei,drei` German words from one to three. Empty words are skipped.
+fn vocabulary<'w>(languages: impl IntoIterator<Item = &'w str>) -> Scanner<u32> {
+    let digits = (1..=9).map(|digit| (digit.to_string(), digit));
+    let words = languages.into_iter().flat_map(|language| {
+        language
+            .split(',')
+            .map(|word| word.trim().to_string())
+            .zip(0..)
+    });
+    Scanner::new(digits.chain(words))
+}
 
-    fn create_digits() -> Vec<Self> {
-        vec![
-            Digit::new("1".to_owned(), 1),
-            Digit::new("2".to_owned(), 2),
-            Digit::new("3".to_owned(), 3),
-            Digit::new("4".to_owned(), 4),
-            Digit::new("5".to_owned(), 5),
-            Digit::new("6".to_owned(), 6),
-            Digit::new("7".to_owned(), 7),
-            Digit::new("8".to_owned(), 8),
-            Digit::new("9".to_owned(), 9),
-        ]
-    }
+/// The first and the last digit found in `line` as a two digit number.
+fn calibration(line: &str, vocabulary: &Scanner<u32>) -> Option<u32> {
+    let (first, last) = vocabulary.first_and_last(line)?;
+    Some(first.value * 10 + last.value)
 }
 
-fn find_digit(text: &str, digits: &[Digit]) -> Option<u32> {
-    return digits
-        .iter()
-        .map(|digit| (digit.value, text.find(&digit.text)))
-        .filter(|(_, index)| index.is_some())
-        .min_by(|(_, a), (_, b)| a.cmp(b))
-        .map(|(value, _)| value);
+/// The sum of all calibration values, None if a line has no digit.
+fn solve(input: &str, vocabulary: &Scanner<u32>) -> Option<u32> {
+    input
+        .lines()
+        .map(|line| calibration(line, vocabulary))
+        .sum()
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let digits = Digit::create_digits();
-    let result = input
-        .lines()
-        .map(|it| {
-            (
-                find_digit(it, &digits).unwrap(),
-                find_digit(it.chars().rev().collect::<String>().as_str(), &digits).unwrap(),
-            )
-        })
-        .map(|fix| fix.0 * 10 + fix.1)
-        .sum();
-    Some(result)
+    solve(input, &vocabulary([]))
 }
 
+/// `--words <words>` adds another language or word to the vocabulary, see [`vocabulary`].
 pub fn part_two(input: &str) -> Option<u32> {
-    let mut digits2 = Digit::create_digits();
-    digits2.extend(vec![
-        Digit::new("one".to_owned(), 1),
-        Digit::new("two".to_owned(), 2),
-        Digit::new("three".to_owned(), 3),
-        Digit::new("four".to_owned(), 4),
-        Digit::new("five".to_owned(), 5),
-        Digit::new("six".to_owned(), 6),
-        Digit::new("seven".to_owned(), 7),
-        Digit::new("eight".to_owned(), 8),
-        Digit::new("nine".to_owned(), 9),
-    ]);
+    let mut args = pico_args::Arguments::from_env();
+    let mut languages = vec![WORDS.join(",")];
+    match args.values_from_str::<_, String>("--words") {
+        Ok(words) => languages.extend(words),
+        Err(e) => eprintln!("Ignoring --words: {e}"),
+    }
+    solve(input, &vocabulary(languages.iter().map(String::as_str)))
+}
 
-    let digits_rev = digits2
-        .iter()
-        .map(|it| Digit::new(it.text.chars().rev().collect::<String>(), it.value))
-        .collect::<Vec<_>>();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let result = input
-        .lines()
-        .map(|it| {
-            (
-                find_digit(it, digits2.as_slice()).unwrap(),
-                find_digit(
-                    it.chars().rev().collect::<String>().as_str(),
-                    digits_rev.as_slice(),
-                )
-                .unwrap(),
-            )
-        })
-        .map(|fix| fix.0 * 10 + fix.1)
-        .sum();
-    Some(result)
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(142));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file_part(
+            "examples", DAY, 2,
+        ));
+        assert_eq!(result, Some(281));
+    }
+
+    #[test]
+    fn test_overlapping_words() {
+        let english = vocabulary([WORDS.join(",").as_str()]);
+        assert_eq!(calibration("eightwo", &english), Some(82));
+        assert_eq!(calibration("oneight", &english), Some(18));
+        assert_eq!(calibration("twone", &english), Some(21));
+        assert_eq!(calibration("sevenine", &english), Some(79));
+        assert_eq!(calibration("xyz", &english), None);
+        assert_eq!(solve("1\nxyz\n", &english), None);
+    }
+
+    #[test]
+    fn test_vocabulary() {
+        let digits = vocabulary([]);
+        assert_eq!(calibration("one2three0", &digits), Some(22));
+
+        let zero = vocabulary(["zero", WORDS.join(",").as_str()]);
+        assert_eq!(calibration("zeroone", &zero), Some(1));
+        assert_eq!(calibration("nine0zero", &zero), Some(90));
+
+        let german = vocabulary([",eins,zwei,drei,vier,fünf"]);
+        assert_eq!(calibration("fünfundzwei", &german), Some(52));
+        assert_eq!(calibration("dreione", &german), Some(33));
+    }
 }
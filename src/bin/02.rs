@@ -1,81 +1,217 @@
-use std::collections::HashMap;
+use std::{fmt::Display, str::FromStr};
 
-advent_of_code::solution!(2);
+use advent_of_code::parse::{self, ParseError};
+use itertools::Itertools;
+
+advent_of_code::solution!(2, extras = extras);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Color {
+    Red,
+    Green,
+    Blue,
+}
+
+const COLORS: [Color; 3] = [Color::Red, Color::Green, Color::Blue];
+
+impl Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Color::Red => "red",
+            Color::Green => "green",
+            Color::Blue => "blue",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        COLORS
+            .into_iter()
+            .find(|color| color.to_string() == s.trim())
+            .ok_or_else(|| format!("unknown color `{}`", s.trim()))
+    }
+}
+
+/// A number of cubes per color, e.g. the cubes shown in one round or the cubes in the bag.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Bag {
+    cubes: [u32; 3],
+}
+
+/// The bag of part one.
+const ELF_BAG: Bag = Bag {
+    cubes: [12, 13, 14],
+};
+
+impl Bag {
+    fn get(&self, color: Color) -> u32 {
+        self.cubes[color as usize]
+    }
+
+    fn with(mut self, color: Color, count: u32) -> Self {
+        self.cubes[color as usize] = count;
+        self
+    }
+
+    /// Whether this bag has at least as many cubes of every color as `other`.
+    fn contains(&self, other: &Bag) -> bool {
+        COLORS
+            .iter()
+            .all(|&color| self.get(color) >= other.get(color))
+    }
+
+    /// The most cubes of every color of both bags.
+    fn union(&self, other: &Bag) -> Bag {
+        COLORS.iter().fold(*self, |bag, &color| {
+            bag.with(color, self.get(color).max(other.get(color)))
+        })
+    }
+
+    fn power(&self) -> u32 {
+        self.cubes.iter().product()
+    }
+}
+
+impl Display for Bag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cubes = COLORS
+            .iter()
+            .map(|&color| format!("{} {color}", self.get(color)))
+            .join(", ");
+        write!(f, "{cubes}")
+    }
+}
+
+impl FromStr for Bag {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_bag(s, s)
+    }
+}
+
+/// Cubes like `3 blue, 4 red`, colors that are not mentioned have none. `cubes` is a slice of
+/// `text`, errors are positioned within `text`.
+fn parse_bag(text: &str, cubes: &str) -> Result<Bag, ParseError> {
+    cubes.split(',').try_fold(Bag::default(), |bag, cubes| {
+        let cubes = cubes.trim();
+        let (count, color) = cubes.split_once(' ').ok_or_else(|| {
+            ParseError::at(
+                text,
+                cubes,
+                format!("expected `<count> <color>`, found `{cubes}`"),
+            )
+        })?;
+        let color = color
+            .parse::<Color>()
+            .map_err(|e| ParseError::at(text, color, e))?;
+        Ok(bag.with(color, parse::value(text, count)?))
+    })
+}
 
 #[derive(Debug)]
 struct Game {
     id: u32,
-    rounds: Vec<HashMap<String, u32>>,
-}
-
-fn parse(input: &str) -> Vec<Game> {
-    let mut games = Vec::new();
-    for line in input.lines() {
-        let parts = line.split(':').collect::<Vec<_>>();
-        let name = parts[0].to_string();
-
-        let id = name.split(' ').nth(1).unwrap().parse::<u32>().unwrap();
-        let rounds = parts[1]
-            .trim()
-            .split(';')
-            .map(|set| {
-                set.trim()
-                    .split(',')
-                    .map(|ball| {
-                        let mut parts = ball.trim().split(' ').map(|it| it.trim());
-                        let count = parts.next().unwrap().trim().parse::<u32>().unwrap();
-                        let color = parts.next().unwrap().trim().to_owned();
-                        (color, count)
-                    })
-                    .collect::<HashMap<_, _>>()
-            })
-            .collect::<Vec<_>>();
-        games.push(Game { rounds, id });
+    rounds: Vec<Bag>,
+}
+
+impl Game {
+    /// The fewest cubes of every color that make the game possible.
+    fn minimum_bag(&self) -> Bag {
+        self.rounds
+            .iter()
+            .fold(Bag::default(), |bag, round| bag.union(round))
+    }
+
+    fn is_feasible(&self, bag: &Bag) -> bool {
+        bag.contains(&self.minimum_bag())
     }
+}
+
+fn parse_game(line: &str) -> Result<Game, ParseError> {
+    let (name, rounds) = parse::split_once(line, ":")?;
+    let id = match name.strip_prefix("Game ") {
+        Some(id) => parse::value(line, id)?,
+        None => return Err(ParseError::at(line, name, "expected `Game <id>`")),
+    };
+    let rounds = rounds
+        .split(';')
+        .map(|round| parse_bag(line, round))
+        .collect::<Result<_, _>>()?;
+    Ok(Game { id, rounds })
+}
+
+/// The ids of the games that `bag` makes possible.
+fn feasible_games(games: &[Game], bag: &Bag) -> Vec<u32> {
+    games
+        .iter()
+        .filter(|game| game.is_feasible(bag))
+        .map(|game| game.id)
+        .collect()
+}
+
+/// The ids of the games that are impossible with `bag`, but possible with `more` additional
+/// cubes of `color`.
+fn newly_feasible(games: &[Game], bag: &Bag, color: Color, more: u32) -> Vec<u32> {
+    let bigger = bag.with(color, bag.get(color).saturating_add(more));
     games
+        .iter()
+        .filter(|game| !game.is_feasible(bag) && game.is_feasible(&bigger))
+        .map(|game| game.id)
+        .collect()
+}
+
+fn parse_increase(s: &str) -> Result<(Color, u32), String> {
+    let (color, more) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected `<color>=<count>`, got `{s}`"))?;
+    let more = more
+        .trim()
+        .parse()
+        .map_err(|e| format!("invalid count `{more}`: {e}"))?;
+    Ok((color.parse()?, more))
+}
+
+/// `--bag "12 red, 13 green, 14 blue"` replaces the bag of the elf.
+fn bag_option() -> Bag {
+    let mut args = pico_args::Arguments::from_env();
+    match args.opt_value_from_str::<_, Bag>("--bag") {
+        Ok(bag) => bag.unwrap_or(ELF_BAG),
+        Err(e) => {
+            eprintln!("Ignoring --bag: {e}");
+            ELF_BAG
+        }
+    }
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let games = parse(input);
-    let valid_games = games.iter().filter(|g| {
-        g.rounds.iter().all(|r| {
-            r.get("red").cloned().unwrap_or_default() <= 12
-                && r.get("green").cloned().unwrap_or_default() <= 13
-                && r.get("blue").cloned().unwrap_or_default() <= 14
-        })
-    });
+    let games = parse::parse_lines(input, parse_game).ok()?;
+    Some(feasible_games(&games, &bag_option()).iter().sum())
+}
 
-    let sum = valid_games.map(|g| g.id).sum::<u32>();
-    Some(sum)
+/// `--increase red=3` lists the games that three more red cubes would make possible.
+fn extras(input: &str) {
+    let mut args = pico_args::Arguments::from_env();
+    let increase = match args.opt_value_from_fn("--increase", parse_increase) {
+        Ok(increase) => increase,
+        Err(e) => return eprintln!("Ignoring --increase: {e}"),
+    };
+    let (Some((color, more)), Ok(games)) = (increase, parse::parse_lines(input, parse_game)) else {
+        return;
+    };
+    println!(
+        "{more} more {color} would make games {:?} possible",
+        newly_feasible(&games, &bag_option(), color, more)
+    );
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let games = parse(input);
-    let minimum_games = games
-        .iter()
-        .map(|g| {
-            let red = g
-                .rounds
-                .iter()
-                .filter_map(|r| r.get("red"))
-                .max()
-                .unwrap_or(&1);
-            let green = g
-                .rounds
-                .iter()
-                .filter_map(|r| r.get("green"))
-                .max()
-                .unwrap_or(&1);
-            let blue = g
-                .rounds
-                .iter()
-                .filter_map(|r| r.get("blue"))
-                .max()
-                .unwrap_or(&1);
-            red * green * blue
-        })
-        .collect::<Vec<_>>();
-    Some(minimum_games.iter().sum())
+    let games = parse::parse_lines(input, parse_game).ok()?;
+    Some(games.iter().map(|game| game.minimum_bag().power()).sum())
 }
 
 #[cfg(test)]
@@ -93,4 +229,46 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(2286));
     }
+
+    #[test]
+    fn test_bag() {
+        let bag = "4 red, 3 blue".parse::<Bag>().unwrap();
+        assert_eq!(bag, Bag { cubes: [4, 0, 3] });
+        assert_eq!(bag.to_string(), "4 red, 0 green, 3 blue");
+        assert_eq!(ELF_BAG.to_string().parse(), Ok(ELF_BAG));
+        assert!(ELF_BAG.contains(&bag));
+        assert!(!bag.contains(&ELF_BAG));
+        assert_eq!(bag.power(), 0);
+        assert!("4 purple".parse::<Bag>().is_err());
+        assert!("red 4".parse::<Bag>().is_err());
+        assert!(parse_game("Game x: 1 red").is_err());
+
+        // positioned within the whole line, not the round
+        let error = parse_game("Game 1: 3 blue; 2 purple").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 19));
+        let error = parse_game("Game 1: 3 blue; 2 red, x green").unwrap_err();
+        assert_eq!(error.column, 24);
+        let error = parse_game("Game 1: 3 blue; 2red").unwrap_err();
+        assert_eq!(error.column, 17);
+    }
+
+    #[test]
+    fn test_queries() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let games = parse::parse_lines(&input, parse_game).unwrap();
+        assert_eq!(games[0].minimum_bag(), Bag { cubes: [4, 2, 6] });
+        assert_eq!(feasible_games(&games, &ELF_BAG), [1, 2, 5]);
+        // game 3 needs 20 red, game 4 needs 14 red and 15 blue
+        assert_eq!(newly_feasible(&games, &ELF_BAG, Color::Red, 8), [3]);
+        assert_eq!(newly_feasible(&games, &ELF_BAG, Color::Red, 7), []);
+        assert_eq!(newly_feasible(&games, &ELF_BAG, Color::Blue, 1), []);
+        let more_blue = ELF_BAG.with(Color::Blue, 15);
+        assert_eq!(newly_feasible(&games, &more_blue, Color::Red, 2), [4]);
+        assert_eq!(
+            newly_feasible(&games, &more_blue, Color::Red, u32::MAX),
+            [3, 4]
+        );
+        assert_eq!(parse_increase("red=3"), Ok((Color::Red, 3)));
+        assert!(parse_increase("red").is_err());
+    }
 }
@@ -1,5 +1,6 @@
 advent_of_code::solution!(4);
 
+use advent_of_code::memo::fix;
 use std::{
     collections::{HashMap, HashSet},
     str::FromStr,
@@ -47,40 +48,18 @@ pub fn part_one(input: &str) -> Option<u32> {
         .collect::<Result<Vec<_>, _>>()
         .unwrap();
 
-    let number_of_matches = cards
-        .iter()
-        .map(|c| {
-            c.numbers
-                .iter()
-                .filter(|n| c.winning_numbers.contains(n))
-                .count() as u32
-        })
-        .filter(|it| *it > 0);
+    let number_of_matches = cards.iter().map(count_matches).filter(|it| *it > 0);
 
     let points = number_of_matches.map(|it| it - 1).map(|it| 2u32.pow(it));
 
     Some(points.sum())
 }
 
-fn count_cards(cards: &HashMap<u32, Card>, number: u32, memory: &mut HashMap<u32, u32>) -> u32 {
-    if let Some(result) = memory.get(&number) {
-        return *result;
-    }
-
-    let card = cards.get(&number).unwrap();
-    let number_of_matches = card
-        .numbers
+fn count_matches(card: &Card) -> u32 {
+    card.numbers
         .iter()
         .filter(|n| card.winning_numbers.contains(n))
-        .count() as u32;
-
-    let result = 1
-        + (number + 1..=(number + number_of_matches))
-            .map(|c| count_cards(cards, c, memory))
-            .sum::<u32>();
-
-    memory.insert(number, result);
-    result
+        .count() as u32
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
@@ -90,13 +69,15 @@ pub fn part_two(input: &str) -> Option<u32> {
         .map(|it| (it.card_number, it))
         .collect::<HashMap<_, _>>();
 
-    let mut memory = HashMap::new();
-    Some(
-        cards
-            .values()
-            .map(|c| count_cards(&cards, c.card_number, &mut memory))
-            .sum(),
-    )
+    // the number of cards we end up with when holding one copy of the given card
+    let mut count_cards = fix(|count_cards, number: u32| {
+        let card = cards.get(&number).unwrap();
+        1 + (number + 1..=(number + count_matches(card)))
+            .map(count_cards)
+            .sum::<u32>()
+    });
+
+    Some(cards.keys().map(|n| count_cards.call(*n)).sum())
 }
 
 #[cfg(test)]
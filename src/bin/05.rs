@@ -1,12 +1,27 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, ops::Range};
+
+use advent_of_code::parse::{self, ParseError};
 
 advent_of_code::solution!(5);
 
+const FIRST_CATEGORY: &str = "seed";
+const LAST_CATEGORY: &str = "location";
+
 #[derive(Debug)]
 struct ConversionRange {
-    destination_start: u32,
-    source_start: u32,
-    length: u32,
+    destination_start: u64,
+    source_start: u64,
+    length: u64,
+}
+
+impl ConversionRange {
+    fn source(&self) -> Range<u64> {
+        self.source_start..self.source_start + self.length
+    }
+
+    fn convert(&self, value: u64) -> u64 {
+        value - self.source_start + self.destination_start
+    }
 }
 
 #[derive(Debug)]
@@ -16,33 +31,133 @@ struct Mapping {
     ranges: Vec<ConversionRange>,
 }
 
-fn parse_mappings(input: &str) -> (Vec<u32>, HashMap<String, Mapping>) {
-    let mut lines = input.lines();
+impl Mapping {
+    /// converts a single value, values not covered by any range map to themselves
+    fn convert(&self, value: u64) -> u64 {
+        self.ranges
+            .iter()
+            .find(|r| r.source().contains(&value))
+            .map(|r| r.convert(value))
+            .unwrap_or(value)
+    }
+
+    /// converts whole ranges by splitting them at the borders of the conversion ranges
+    fn convert_ranges(&self, ranges: Vec<Range<u64>>) -> Vec<Range<u64>> {
+        let mut unmapped = ranges;
+        let mut mapped = Vec::new();
+
+        for conversion in &self.ranges {
+            let source = conversion.source();
+            let mut remaining = Vec::new();
+            for r in unmapped {
+                let overlap = r.start.max(source.start)..r.end.min(source.end);
+                if overlap.is_empty() {
+                    remaining.push(r);
+                    continue;
+                }
+
+                mapped.push(
+                    conversion.convert(overlap.start)..conversion.convert(overlap.end - 1) + 1,
+                );
+                // keep the parts left and right of the overlap for the next conversion range
+                if r.start < overlap.start {
+                    remaining.push(r.start..overlap.start);
+                }
+                if overlap.end < r.end {
+                    remaining.push(overlap.end..r.end);
+                }
+            }
+            unmapped = remaining;
+        }
+
+        // whatever was not touched by a conversion range maps to itself
+        mapped.extend(unmapped);
+        mapped
+    }
+}
+
+/// the ordered list of mappings that leads from one category to another
+struct Chain<'m> {
+    mappings: Vec<&'m Mapping>,
+}
+
+impl<'m> Chain<'m> {
+    /// follows [`Mapping::to`] from `from` until we reach `to`
+    fn resolve(mappings: &'m HashMap<String, Mapping>, from: &str, to: &str) -> Option<Self> {
+        let mut chain = Vec::new();
+        let mut category = from;
+        while category != to {
+            let mapping = mappings.get(category)?;
+            if chain.len() == mappings.len() {
+                // we visited more mappings than there are, so we are running in circles
+                return None;
+            }
+            chain.push(mapping);
+            category = mapping.to.as_str();
+        }
+        Some(Chain { mappings: chain })
+    }
+
+    fn convert(&self, value: u64) -> u64 {
+        self.mappings.iter().fold(value, |v, m| m.convert(v))
+    }
 
-    let seeds_line = lines.next().unwrap();
-    let seeds = seeds_line
-        .trim_start_matches("seeds: ")
-        .split(" ")
-        .map(|s| s.parse::<u32>().unwrap())
-        .collect::<Vec<u32>>();
+    fn convert_ranges(&self, ranges: Vec<Range<u64>>) -> Vec<Range<u64>> {
+        self.mappings
+            .iter()
+            .fold(ranges, |ranges, m| m.convert_ranges(ranges))
+    }
+
+    /// lists every category the value passes through, starting with the value itself
+    fn trace(&self, value: u64) -> Vec<(&str, u64)> {
+        let mut trace = Vec::with_capacity(self.mappings.len() + 1);
+        let mut value = value;
+        if let Some(first) = self.mappings.first() {
+            trace.push((first.from.as_str(), value));
+        }
+        for m in &self.mappings {
+            value = m.convert(value);
+            trace.push((m.to.as_str(), value));
+        }
+        trace
+    }
+
+    fn categories(&self) -> Vec<&str> {
+        self.mappings
+            .first()
+            .map(|m| m.from.as_str())
+            .into_iter()
+            .chain(self.mappings.iter().map(|m| m.to.as_str()))
+            .collect()
+    }
+}
+
+fn parse_mappings(input: &str) -> Result<(Vec<u64>, HashMap<String, Mapping>), ParseError> {
+    let sections = parse::sections(input);
+    let (seeds_section, mapping_sections) = sections
+        .split_first()
+        .ok_or_else(|| ParseError::new(1, 1, "expected a line of seeds"))?;
+    let seeds = parse::integers::<u64>(seeds_section.text)?;
 
-    lines.next(); //empty line
     let mut mappings = HashMap::new();
-    while let Some(line) = lines.next() {
-        let mut ranges = Vec::new();
-        let (from, to) = line.trim_end_matches(" map:").split_once("-to-").unwrap();
-        while let Some(l) = lines.next().filter(|it| !it.trim().is_empty()) {
-            let [destination_start, source_start, length]: [&str; 3] =
-                l.splitn(3, ' ').collect::<Vec<_>>().try_into().unwrap();
-            let destination_start = destination_start.parse::<u32>().unwrap();
-            let source_start = source_start.parse::<u32>().unwrap();
-            let length = length.parse::<u32>().unwrap();
-            ranges.push(ConversionRange {
+    for section in mapping_sections {
+        let (header, ranges) =
+            parse::split_once(section.text, "\n").map_err(|e| e.shifted(section.line_offset))?;
+        let (from, to) = parse::split_once(header.trim_end_matches(" map:"), "-to-")
+            .map_err(|e| e.shifted(section.line_offset))?;
+        let ranges = parse::parse_lines(ranges, |l| {
+            let [destination_start, source_start, length] =
+                parse::integers::<u64>(l)?
+                    .try_into()
+                    .map_err(|_| ParseError::at(l, l, "expected three numbers"))?;
+            Ok(ConversionRange {
                 destination_start,
                 source_start,
                 length,
-            });
-        }
+            })
+        })
+        .map_err(|e| e.shifted(section.line_offset + 1))?;
+
         mappings.insert(
             from.to_string(),
             Mapping {
@@ -53,23 +168,121 @@ fn parse_mappings(input: &str) -> (Vec<u32>, HashMap<String, Mapping>) {
         );
     }
 
-    (seeds, mappings)
+    Ok((seeds, mappings))
+}
+
+/// Each seed's way through the categories, one line per seed below a line of the categories.
+pub fn trace_seeds(input: &str) -> Option<String> {
+    let (seeds, mappings) = parse_mappings(input).ok()?;
+    let chain = Chain::resolve(&mappings, FIRST_CATEGORY, LAST_CATEGORY)?;
+
+    let mut out = chain.categories().join(" -> ") + "\n";
+    for seed in seeds {
+        let trace = chain.trace(seed);
+        out += &trace
+            .iter()
+            .map(|(_, v)| v.to_string())
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        out += "\n";
+    }
+    Some(out)
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let (seeds, mappings) = dbg!(parse_mappings(input));
+pub fn part_one(input: &str) -> Option<u64> {
+    let (seeds, mappings) = parse_mappings(input).ok()?;
+    let chain = Chain::resolve(&mappings, FIRST_CATEGORY, LAST_CATEGORY)?;
 
-    None
+    seeds.iter().map(|s| chain.convert(*s)).min()
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    None
+pub fn part_two(input: &str) -> Option<u64> {
+    let (seeds, mappings) = parse_mappings(input).ok()?;
+    let chain = Chain::resolve(&mappings, FIRST_CATEGORY, LAST_CATEGORY)?;
+
+    let seed_ranges = seeds
+        .chunks_exact(2)
+        .map(|it| it[0]..it[0] + it[1])
+        .collect::<Vec<_>>();
+
+    chain
+        .convert_ranges(seed_ranges)
+        .iter()
+        .map(|r| r.start)
+        .min()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_chain() {
+        let (_, mappings) =
+            parse_mappings(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        let chain = Chain::resolve(&mappings, "seed", "location").unwrap();
+        assert_eq!(
+            chain.categories(),
+            vec![
+                "seed",
+                "soil",
+                "fertilizer",
+                "water",
+                "light",
+                "temperature",
+                "humidity",
+                "location"
+            ]
+        );
+
+        assert!(Chain::resolve(&mappings, "seed", "unknown").is_none());
+    }
+
+    #[test]
+    fn test_trace() {
+        let (_, mappings) =
+            parse_mappings(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        let chain = Chain::resolve(&mappings, "seed", "location").unwrap();
+        let values = chain
+            .trace(79)
+            .into_iter()
+            .map(|(_, v)| v)
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![79, 81, 81, 81, 74, 78, 78, 82]);
+
+        let trace = trace_seeds(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        let lines = trace.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(
+            lines[0],
+            "seed -> soil -> fertilizer -> water -> light -> temperature -> humidity -> location"
+        );
+        assert_eq!(lines[1], "79 -> 81 -> 81 -> 81 -> 74 -> 78 -> 78 -> 82");
+        assert_eq!(lines[2], "14 -> 14 -> 53 -> 49 -> 42 -> 42 -> 43 -> 43");
+    }
+
+    #[test]
+    fn test_convert_ranges() {
+        let mapping = Mapping {
+            from: "a".to_string(),
+            to: "b".to_string(),
+            ranges: vec![ConversionRange {
+                destination_start: 100,
+                source_start: 10,
+                length: 5,
+            }],
+        };
+        let mut result = mapping.convert_ranges(vec![5..20, 30..40]);
+        result.sort_by_key(|r| r.start);
+        assert_eq!(result, vec![5..10, 15..20, 30..40, 100..105]);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse_mappings("seeds: 1 2\n\na-to-b map:\n1 2 3\n4 5\n").unwrap_err();
+        assert_eq!((error.line, error.column), (5, 1));
+    }
+
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
@@ -79,6 +292,6 @@ mod tests {
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(46));
     }
 }
@@ -1,8 +1,9 @@
-advent_of_code::solution!(10);
+advent_of_code::solution!(10, extras = extras);
 
 use std::collections::HashMap;
 use std::collections::HashSet;
 
+use advent_of_code::{polygon::Polygon, render::Svg};
 use lazy_static::lazy_static;
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
@@ -39,119 +40,240 @@ lazy_static! {
 
 struct Map<'i> {
     lines: Vec<&'i str>,
+    start: Vec2D,
+    /// The pipe hidden under `S`.
+    start_tile: char,
 }
 
 impl<'i> Map<'i> {
-    fn get_connectors(&self, position: &Vec2D) -> &Vec<Vec2D> {
+    /// None without a start or if no pipe under the start closes a loop.
+    fn new(input: &'i str) -> Option<Self> {
+        let lines: Vec<&str> = input.lines().collect();
+        let start = lines
+            .iter()
+            .enumerate()
+            .find_map(|(y, line)| line.find('S').map(|x| Vec2D::new(x as i32, y as i32)))?;
+        let mut map = Self {
+            lines,
+            start,
+            start_tile: 'S',
+        };
+        // stray pipes may point at the start as well, only the right pipe closes the loop
+        map.start_tile = map.start_candidates().into_iter().find(|&tile| {
+            map.start_tile = tile;
+            collect_path(&map).is_some()
+        })?;
+        Some(map)
+    }
+
+    /// The pipes that could be under `S`, those connecting two neighbours that connect back.
+    fn start_candidates(&self) -> Vec<char> {
+        let connects_back = |dir: &Vec2D| {
+            self.get_connectors(&self.start.add(dir))
+                .iter()
+                .any(|d| d.x == -dir.x && d.y == -dir.y)
+        };
+        "|-LJ7F"
+            .chars()
+            .filter(|tile| TILES[tile].iter().all(connects_back))
+            .collect()
+    }
+
+    /// The tile at `position` with `S` replaced by its pipe, `.` outside the map.
+    fn get_tile(&self, position: &Vec2D) -> char {
         if position.y < 0 || position.y >= self.lines.len() as i32 {
-            return &EMPTY;
+            return '.';
         }
-        let row = self.lines.get(position.y as usize).unwrap();
-
+        let row = self.lines[position.y as usize];
         if position.x < 0 || position.x >= row.len() as i32 {
-            return &EMPTY;
+            return '.';
         }
-        let character = row.as_bytes()[position.x as usize] as char;
-        TILES.get(&character).unwrap_or(&EMPTY)
+        match row.as_bytes()[position.x as usize] as char {
+            'S' => self.start_tile,
+            tile => tile,
+        }
+    }
+
+    fn get_connectors(&self, position: &Vec2D) -> &Vec<Vec2D> {
+        TILES.get(&self.get_tile(position)).unwrap_or(&EMPTY)
     }
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let map = Map {
-        lines: input.lines().collect(),
-    };
+    let map = Map::new(input)?;
 
-    let path = collect_path(&map);
+    let path = collect_path(&map)?;
     Some((path.len() / 2) as u32)
 }
 
-fn collect_path(map: &Map<'_>) -> HashSet<Vec2D> {
-    let start = map
-        .lines
+/// The tiles of the loop in walking order, beginning with the start. None if the pipes from
+/// the start do not lead back to it.
+fn collect_path(map: &Map<'_>) -> Option<Vec<Vec2D>> {
+    let mut path = vec![map.start];
+    let mut previous = map.start;
+    let mut pos = map.start.add(map.get_connectors(&map.start).first()?);
+    while pos != map.start {
+        path.push(pos);
+        let neighbours = map
+            .get_connectors(&pos)
+            .iter()
+            .map(|dir| pos.add(dir))
+            .collect::<Vec<_>>();
+        // the pipe has to connect back to where we came from
+        if !neighbours.contains(&previous) {
+            return None;
+        }
+        //find connected tile we did not come from
+        let next = neighbours
+            .into_iter()
+            .find(|next_pos| *next_pos != previous)?;
+        previous = pos;
+        pos = next;
+    }
+    // the start has to connect to the last tile as well
+    map.get_connectors(&map.start)
         .iter()
-        .enumerate()
-        .find_map(|(y, line)| line.find('S').map(|x| Vec2D::new(x as i32, y as i32)))
-        .expect("no start point found");
+        .any(|dir| map.start.add(dir) == previous)
+        .then_some(path)
+}
 
-    let mut path = HashSet::new();
-    path.insert(start);
+/// The loop as a lattice polygon through the tile centers, every tile is a vertex.
+fn loop_polygon(path: &[Vec2D]) -> Polygon {
+    Polygon::new(path.iter().map(|p| (p.x as i128, p.y as i128)).collect())
+}
 
-    // find next step
-    let directions = vec![*N, *S, *E, *W];
-    let mut pos = start;
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Class {
+    Loop,
+    Inside,
+    Outside,
+}
 
-    // help with first step from Start
-    let step = directions
-        .iter()
-        .find(|dir| {
-            let connectors = map.get_connectors(&pos.add(dir));
-            // see if it would connect back to us
-            connectors.iter().any(|d| d.x == -dir.x && d.y == -dir.y)
-        })
-        .expect("no first step found");
-
-    pos = pos.add(step);
-    path.insert(pos);
-    while !path.len() > 1 && pos != start {
-        //find connected tile
-        let connectors = map.get_connectors(&pos);
-        pos = connectors
+/// Every tile of the map as part of the loop, enclosed by it or outside of it.
+struct Classification {
+    path: Vec<Vec2D>,
+    rows: Vec<Vec<Class>>,
+}
+
+impl Classification {
+    /// Scans each row once, crossing the loop flips between outside and inside. Only tiles
+    /// with a pipe going north count as a crossing, so a run like `L--7` flips once and `L--J`
+    /// not at all.
+    fn new(map: &Map<'_>, path: Vec<Vec2D>) -> Self {
+        let on_loop: HashSet<Vec2D> = path.iter().copied().collect();
+        let rows = map
+            .lines
             .iter()
-            .map(|dir| pos.add(dir)) // connected neighbours
-            .find(|next_pos| {
-                // find the one that is not in path
-                !path.contains(next_pos) || (next_pos == &start && path.len() > 2)
+            .enumerate()
+            .map(|(y, line)| {
+                let mut inside = false;
+                (0..line.len() as i32)
+                    .map(|x| {
+                        let position = Vec2D::new(x, y as i32);
+                        if !on_loop.contains(&position) {
+                            return if inside {
+                                Class::Inside
+                            } else {
+                                Class::Outside
+                            };
+                        }
+                        if matches!(map.get_tile(&position), '|' | 'L' | 'J') {
+                            inside = !inside;
+                        }
+                        Class::Loop
+                    })
+                    .collect()
             })
-            .unwrap_or_else(|| panic!("no next step found for {:?}", pos));
-
-        path.insert(pos);
+            .collect();
+        Self { path, rows }
     }
-    path
-}
 
-fn count_walls(map: &Map<'_>, path: &HashSet<Vec2D>, x: i32, y: i32, delta_x: i32) -> i32 {
-    let line = map.lines[y as usize];
+    /// The loop in box-drawing characters, enclosed tiles as `I` and the rest as `O`.
+    fn render(&self, map: &Map<'_>) -> String {
+        let mut out = String::new();
+        for (y, row) in self.rows.iter().enumerate() {
+            for (x, class) in row.iter().enumerate() {
+                out.push(match class {
+                    Class::Inside => 'I',
+                    Class::Outside => 'O',
+                    Class::Loop => match map.get_tile(&Vec2D::new(x as i32, y as i32)) {
+                        '|' => '│',
+                        '-' => '─',
+                        'L' => '└',
+                        'J' => '┘',
+                        '7' => '┐',
+                        'F' => '┌',
+                        tile => tile,
+                    },
+                });
+            }
+            out.push('\n');
+        }
+        out
+    }
 
-    let mut count = 0;
-    let mut x = x;
-    while x >= 0 && x < line.len() as i32 {
-        if path.contains(&Vec2D::new(x, y)) {
-            let mut char = map.lines[y as usize].as_bytes()[x as usize] as char;
-            if char == 'S' {
-                char = '|'; // todo specific for my data!
+    /// Enclosed tiles in green, the loop as a line through the tile centers.
+    fn to_svg(&self) -> Svg {
+        const CELL: usize = 12;
+        let width = self.rows.iter().map(Vec::len).max().unwrap_or(0);
+        let mut svg = Svg::new(width * CELL, self.rows.len() * CELL);
+        for (y, row) in self.rows.iter().enumerate() {
+            for (x, class) in row.iter().enumerate() {
+                let fill = match class {
+                    Class::Loop => "#404040",
+                    Class::Inside => "#2e8b57",
+                    Class::Outside => "#202020",
+                };
+                svg.rect(x * CELL, y * CELL, CELL, CELL, fill);
             }
-            count += match char {
-                '|' | 'L' | 'J' => 1, // LJ count both, but the %2 will fix that
-                _ => 0,
-            };
         }
-        x += delta_x;
+        let center = |p: &Vec2D| {
+            (
+                p.x as usize * CELL + CELL / 2,
+                p.y as usize * CELL + CELL / 2,
+            )
+        };
+        let mut points = self.path.iter().map(center).collect::<Vec<_>>();
+        points.extend(self.path.first().map(center));
+        svg.polyline(&points, "gold", 2);
+        if let Some(start) = self.path.first().map(center) {
+            svg.circle(start.0, start.1, CELL / 3, "red");
+        }
+        svg
     }
-    count
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let map = Map {
-        lines: input.lines().collect(),
+/// `--classified` prints the classified tiles, `--svg <file>` draws them into `file`.
+fn extras(input: &str) {
+    let mut args = pico_args::Arguments::from_env();
+    let classified = args.contains("--classified");
+    let svg = args.opt_value_from_str::<_, String>("--svg");
+    if !classified && !matches!(svg, Ok(Some(_))) {
+        return;
+    }
+    let Some((map, path)) = Map::new(input).and_then(|map| collect_path(&map).map(|p| (map, p)))
+    else {
+        return;
     };
-
-    let path = collect_path(&map);
-    //repair the start
-
-    let mut count = 0u32;
-    //horizontal ray casting
-    for (y, line) in map.lines.iter().enumerate() {
-        for (x, _) in line.chars().enumerate() {
-            if !path.contains(&Vec2D::new(x as i32, y as i32)) {
-                let walls = count_walls(&map, &path, x as i32, y as i32, 1);
-
-                if walls % 2 == 1 {
-                    count += 1;
-                }
-            }
+    let classification = Classification::new(&map, path);
+    if classified {
+        print!("{}", classification.render(&map));
+    }
+    if let Ok(Some(file)) = svg {
+        match std::fs::write(&file, classification.to_svg().to_string()) {
+            Ok(_) => println!("Wrote classification to {file}"),
+            Err(e) => eprintln!("Failed to write classification to {file}: {e}"),
         }
     }
-    Some(count)
+}
+
+pub fn part_two(input: &str) -> Option<u32> {
+    let map = Map::new(input)?;
+
+    let path = collect_path(&map)?;
+    // the tile centers of the loop are the boundary points, the enclosed tiles the interior ones
+    let enclosed = loop_polygon(&path).interior_points();
+    u32::try_from(enclosed).ok()
 }
 
 #[cfg(test)]
@@ -161,12 +283,95 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(8));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        let result = part_two(&advent_of_code::template::read_file_part(
+            "examples", DAY, 2,
+        ));
+        assert_eq!(result, Some(10));
+    }
+
+    #[test]
+    fn test_start_tile() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let map = Map::new(&input).unwrap();
+        assert_eq!(map.start, Vec2D::new(0, 2));
+        assert_eq!(map.start_tile, 'F');
+        let input = advent_of_code::template::read_file_part("examples", DAY, 2);
+        assert_eq!(Map::new(&input).unwrap().start_tile, '7');
+
+        // without any pipes around the start, and without a start
+        assert!(Map::new("...\n.S.\n...").is_none());
+        // all four neighbours point at the start, but only `F` closes a loop
+        let input = ".|...\n-S-7.\n.|.|.\n.L-J.\n";
+        assert_eq!(Map::new(input).unwrap().start_tile, 'F');
+        assert_eq!(part_one(input), Some(4));
+        assert!(Map::new("...\n...").is_none());
+        // none of the pipes around the start closes a loop
+        assert!(Map::new(".|.\n-S-\n.|.").is_none());
+        assert_eq!(part_one(".S-\n..."), None);
+        assert_eq!(part_two(".S-\n..."), None);
+    }
+
+    #[test]
+    fn test_path_order() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let path = collect_path(&Map::new(&input).unwrap()).unwrap();
+        assert_eq!(path.len(), 16);
+        assert_eq!(path[0], Vec2D::new(0, 2));
+        // every tile is next to the one before, the last one closes the loop
+        for (a, b) in path.iter().zip(path.iter().cycle().skip(1)) {
+            assert_eq!((a.x - b.x).abs() + (a.y - b.y).abs(), 1);
+        }
+
+        // the start looks fine, but the pipes below it lead nowhere
+        assert!(Map::new("S7\n|.").is_none());
+    }
+
+    #[test]
+    fn test_classification() {
+        let input = "\
+...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........
+";
+        let map = Map::new(input).unwrap();
+        let classification = Classification::new(&map, collect_path(&map).unwrap());
+        assert_eq!(
+            classification.render(&map),
+            "\
+OOOOOOOOOOO
+O┌───────┐O
+O│┌─────┐│O
+O││OOOOO││O
+O││OOOOO││O
+O│└─┐O┌─┘│O
+O│II│O│II│O
+O└──┘O└──┘O
+OOOOOOOOOOO
+"
+        );
+        assert_eq!(classification.path.len(), 46);
+        assert_eq!(loop_polygon(&classification.path).interior_points(), 4);
+
+        let svg = classification.to_svg().to_string();
+        assert_eq!(svg.matches("<rect").count(), 99);
+        assert!(svg.contains(r#"<polyline points="18,18 18,30"#));
+
+        // the start of the second example is a `7`, which must not flip the rows it is on
+        let input = advent_of_code::template::read_file_part("examples", DAY, 2);
+        let map = Map::new(&input).unwrap();
+        let classification = Classification::new(&map, collect_path(&map).unwrap());
+        let inside = classification.rows.iter().flatten();
+        assert_eq!(inside.filter(|c| **c == Class::Inside).count(), 10);
     }
 }
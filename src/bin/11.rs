@@ -1,133 +1,170 @@
-use std::{collections::HashMap, usize};
+use std::str::FromStr;
 
-advent_of_code::solution!(11);
+use itertools::Itertools;
 
+advent_of_code::solution!(11, extras = extras);
+
+/// Galaxies are numbered from 1 in reading order.
 type ID = usize;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct Galaxy {
-    x: i32,
-    y: i32,
-}
-
-type MultiVec<T> = Vec<Vec<T>>;
-struct ParseGalaxiesResult(HashMap<ID, Galaxy>, MultiVec<usize>, MultiVec<usize>);
-
-fn parse_galaxies(input: &str) -> ParseGalaxiesResult {
-    let mut id = 1usize;
-    let mut galaxies = HashMap::new();
-    for (y, line) in input.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            if c != '.' {
-                galaxies.insert(
-                    id,
-                    Galaxy {
-                        x: x as i32,
-                        y: y as i32,
-                    },
-                );
-                id += 1;
-            }
-        }
-    }
-    let (max_x, max_y) = galaxies.values().fold((0, 0), |(max_x, max_y), galaxy| {
-        (max_x.max(galaxy.x), max_y.max(galaxy.y))
-    });
-
-    let cols = (0..=max_x)
-        .map(|tx| {
-            galaxies
-                .iter()
-                .filter(|(_, galaxy)| galaxy.x == tx)
-                .map(|(id, _)| *id)
-                .collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>();
-    let rows = (0..=max_y)
-        .map(|ty| {
-            galaxies
-                .iter()
-                .filter(|(_, galaxy)| galaxy.y == ty)
-                .map(|(id, _)| *id)
-                .collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>();
-
-    ParseGalaxiesResult(galaxies, cols, rows)
+    x: u64,
+    y: u64,
 }
 
-fn get_path(from: ID, to: ID, galaxies: &HashMap<ID, Galaxy>) -> u32 {
-    let from_galaxy = galaxies.get(&from).expect("galaxy not found");
-    let to_galaxy = galaxies.get(&to).expect("galaxy not found");
-    (from_galaxy.x - to_galaxy.x).unsigned_abs() + (from_galaxy.y - to_galaxy.y).unsigned_abs()
+/// How many rows or columns every empty one becomes, per axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Expansion {
+    x: u64,
+    y: u64,
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let ParseGalaxiesResult(mut galaxies, cols, rows) = parse_galaxies(input);
-
-    expand_universe(cols, rows, &mut galaxies, 1);
+impl Expansion {
+    fn uniform(factor: u64) -> Self {
+        Self {
+            x: factor,
+            y: factor,
+        }
+    }
+}
 
-    let mut sum = 0;
-    for start in 1..=galaxies.len() {
-        for end in (start + 1)..=galaxies.len() {
-            let path = get_path(start, end, &galaxies);
-            sum += path;
+impl FromStr for Expansion {
+    type Err = String;
+
+    /// Either one factor for both axes like `10` or one per axis like `10,100`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |factor: &str| match factor.trim().parse::<u64>() {
+            Ok(0) => Err("expansion factors must be at least 1".to_string()),
+            Ok(factor) => Ok(factor),
+            Err(e) => Err(format!("invalid expansion factor {factor:?}: {e}")),
+        };
+        match s.split_once(',') {
+            Some((x, y)) => Ok(Self {
+                x: parse(x)?,
+                y: parse(y)?,
+            }),
+            None => parse(s).map(Self::uniform),
         }
     }
-    Some(sum)
 }
 
-fn expand_universe(
-    cols: Vec<Vec<usize>>,
-    rows: Vec<Vec<usize>>,
-    galaxies: &mut HashMap<usize, Galaxy>,
-    factor: i32,
-) {
-    let empty_cols = cols
+fn parse_galaxies(input: &str) -> Vec<Galaxy> {
+    input
+        .lines()
+        .enumerate()
+        .flat_map(|(y, line)| {
+            line.char_indices()
+                .filter(|(_, c)| *c != '.')
+                .map(move |(x, _)| Galaxy {
+                    x: x as u64,
+                    y: y as u64,
+                })
+        })
+        .collect()
+}
+
+/// Moves every coordinate by the empty lines before it, each of which grows to `factor` lines.
+/// None if a coordinate does not fit into a `u64` any more.
+fn expand_axis(coordinates: &[u64], factor: u64) -> Option<Vec<u64>> {
+    let occupied = coordinates.iter().copied().sorted().dedup().collect_vec();
+    coordinates
         .iter()
+        .map(|&c| {
+            // all coordinates before `c` that hold no galaxy
+            let empty = c - occupied.partition_point(|&o| o < c) as u64;
+            empty
+                .checked_mul(factor - 1)
+                .and_then(|growth| c.checked_add(growth))
+        })
+        .collect()
+}
+
+fn expand_universe(galaxies: &[Galaxy], expansion: Expansion) -> Option<Vec<Galaxy>> {
+    let xs = expand_axis(&galaxies.iter().map(|g| g.x).collect_vec(), expansion.x)?;
+    let ys = expand_axis(&galaxies.iter().map(|g| g.y).collect_vec(), expansion.y)?;
+    Some(
+        xs.into_iter()
+            .zip(ys)
+            .map(|(x, y)| Galaxy { x, y })
+            .collect(),
+    )
+}
+
+/// The sum of `|a - b|` over all pairs, every coordinate is the larger one for the coordinates
+/// sorted before it.
+fn sum_of_differences(coordinates: impl Iterator<Item = u64>) -> u128 {
+    let mut prefix = 0u128;
+    coordinates
+        .sorted_unstable()
         .enumerate()
-        .filter(|(_, col)| col.is_empty())
-        .map(|(tx, _)| tx)
-        .collect::<Vec<_>>();
-    let empty_rows = rows
+        .map(|(i, c)| {
+            let sum = c as u128 * i as u128 - prefix;
+            prefix += c as u128;
+            sum
+        })
+        .sum()
+}
+
+/// The sum of Manhattan distances between all pairs of galaxies in O(n log n).
+fn sum_of_distances(galaxies: &[Galaxy]) -> u128 {
+    sum_of_differences(galaxies.iter().map(|g| g.x))
+        + sum_of_differences(galaxies.iter().map(|g| g.y))
+}
+
+/// The distance of every pair of galaxies, ordered by their ids.
+fn pairwise_distances(galaxies: &[Galaxy]) -> Vec<((ID, ID), u128)> {
+    galaxies
         .iter()
         .enumerate()
-        .filter(|(_, row)| row.is_empty())
-        .map(|(ty, _)| ty)
-        .collect::<Vec<_>>();
-
-    for (_transitive, tx) in empty_cols.iter().enumerate() {
-        cols.iter()
-            .skip(*tx)
-            .flatten()
-            .for_each(|id| galaxies.get_mut(id).expect("galaxy not found").x += factor)
-    }
+        .tuple_combinations()
+        .map(|((i, a), (j, b))| {
+            let distance = a.x.abs_diff(b.x) as u128 + a.y.abs_diff(b.y) as u128;
+            ((i + 1, j + 1), distance)
+        })
+        .collect()
+}
 
-    for (_transitive, ty) in empty_rows.iter().enumerate() {
-        rows.iter()
-            .skip(*ty)
-            .flatten()
-            .for_each(|id| galaxies.get_mut(id).expect("galaxy not found").y += factor)
-    }
+fn solve(input: &str, expansion: Expansion) -> Option<u64> {
+    let galaxies = expand_universe(&parse_galaxies(input), expansion)?;
+    u64::try_from(sum_of_distances(&galaxies)).ok()
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    solve_two(input, 1_000_000 - 1) // -1 to account for the existing empty row/col
+pub fn part_one(input: &str) -> Option<u64> {
+    solve(input, Expansion::uniform(2))
 }
 
-fn solve_two(input: &str, factor: i32) -> Option<u64> {
-    let ParseGalaxiesResult(mut galaxies, cols, rows) = parse_galaxies(input);
+/// `--expansion 10` or `--expansion 10,100` replaces the factor of one million.
+fn expansion_option() -> Expansion {
+    let mut args = pico_args::Arguments::from_env();
+    let expansion = match args.opt_value_from_str("--expansion") {
+        Ok(expansion) => expansion,
+        Err(e) => {
+            eprintln!("Ignoring --expansion: {e}");
+            None
+        }
+    };
+    expansion.unwrap_or(Expansion::uniform(1_000_000))
+}
 
-    expand_universe(cols, rows, &mut galaxies, factor);
+pub fn part_two(input: &str) -> Option<u64> {
+    solve(input, expansion_option())
+}
 
-    let mut sum = 0u64;
-    for start in 1..=galaxies.len() {
-        for end in (start + 1)..=galaxies.len() {
-            let path = get_path(start, end, &galaxies);
-            sum += path as u64;
+/// `--pairs` prints the distance of every pair of galaxies for both parts.
+fn extras(input: &str) {
+    if !pico_args::Arguments::from_env().contains("--pairs") {
+        return;
+    }
+    for (part, expansion) in [(1, Expansion::uniform(2)), (2, expansion_option())] {
+        println!("Part {part}:");
+        let Some(galaxies) = expand_universe(&parse_galaxies(input), expansion) else {
+            continue;
+        };
+        for ((from, to), distance) in pairwise_distances(&galaxies) {
+            println!("{from} -> {to}: {distance}");
         }
     }
-    Some(sum)
 }
 
 #[cfg(test)]
@@ -142,16 +179,76 @@ mod tests {
 
     #[test]
     fn test_part_two() {
-        let result = solve_two(
+        let result = solve(
             &advent_of_code::template::read_file("examples", DAY),
-            10 - 1,
+            Expansion::uniform(10),
         );
         assert_eq!(result, Some(1030));
 
-        let result = solve_two(
+        let result = solve(
             &advent_of_code::template::read_file("examples", DAY),
-            100 - 1,
+            Expansion::uniform(100),
         );
         assert_eq!(result, Some(8410));
     }
+
+    #[test]
+    fn test_pairwise_distances() {
+        let galaxies = expand_universe(
+            &parse_galaxies(&advent_of_code::template::read_file("examples", DAY)),
+            Expansion::uniform(2),
+        )
+        .unwrap();
+        let distances = pairwise_distances(&galaxies);
+        assert_eq!(distances.len(), 36);
+        // the pairs from the puzzle
+        assert!(distances.contains(&((5, 9), 9)));
+        assert!(distances.contains(&((1, 7), 15)));
+        assert!(distances.contains(&((3, 6), 17)));
+        assert!(distances.contains(&((8, 9), 5)));
+        let sum: u128 = distances.iter().map(|(_, d)| d).sum();
+        assert_eq!(sum, sum_of_distances(&galaxies));
+
+        // each axis fits into u64, their sum does not
+        let far = [
+            Galaxy { x: 0, y: 0 },
+            Galaxy {
+                x: u64::MAX,
+                y: u64::MAX,
+            },
+        ];
+        assert_eq!(pairwise_distances(&far), [((1, 2), 2 * u64::MAX as u128)]);
+    }
+
+    #[test]
+    fn test_expansion() {
+        assert_eq!("10".parse(), Ok(Expansion::uniform(10)));
+        assert_eq!("2, 3".parse(), Ok(Expansion { x: 2, y: 3 }));
+        assert!("0".parse::<Expansion>().is_err());
+        assert!("x".parse::<Expansion>().is_err());
+
+        // only the columns grow
+        let input = "#.#\n...\n#..\n";
+        let galaxies = expand_universe(&parse_galaxies(input), Expansion { x: 5, y: 1 }).unwrap();
+        assert_eq!(galaxies[1], Galaxy { x: 6, y: 0 });
+        assert_eq!(galaxies[2], Galaxy { x: 0, y: 2 });
+
+        // far beyond `i32` and `u64` sums
+        let galaxies =
+            expand_universe(&parse_galaxies(input), Expansion::uniform(u32::MAX as u64)).unwrap();
+        assert_eq!(galaxies[1].x, 1 + u32::MAX as u64);
+        // but not beyond `u64` coordinates
+        assert_eq!(
+            expand_universe(&parse_galaxies(input), Expansion::uniform(u64::MAX)),
+            None
+        );
+        assert_eq!(solve(input, Expansion { x: 1, y: u64::MAX }), None);
+        let many = (0..100_000)
+            .map(|i| Galaxy {
+                x: i * 1_000_000_000_000,
+                y: 0,
+            })
+            .collect_vec();
+        assert!(sum_of_distances(&many) > u64::MAX as u128);
+    }
 }
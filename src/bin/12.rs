@@ -1,5 +1,9 @@
+use advent_of_code::{
+    memo::Memo,
+    parse::{self, ParseError},
+};
 use itertools::Itertools;
-use std::collections::HashMap;
+use rayon::prelude::*;
 
 advent_of_code::solution!(12);
 
@@ -12,82 +16,265 @@ struct Line {
 const FREE: char = '.';
 const SPRING: char = '#';
 
-fn parse_line(line: &str) -> Line {
-    let (springs, groups_str) = line.split_once(' ').unwrap();
+fn parse_line(line: &str) -> Result<Line, ParseError> {
+    let (springs, groups_str) = parse::split_once(line, " ")?;
     let groups = groups_str
         .split(',')
-        .map(|it| it.parse::<usize>().unwrap())
-        .collect();
-    Line {
+        .map(|group| parse::value(line, group))
+        .collect::<Result<_, _>>()?;
+    Ok(Line {
         line: springs.to_string(),
         groups,
-    }
+    })
 }
 
 type Memento = (usize, usize);
 
 fn count_groups(
     line: &str,
-    groups: &Vec<usize>,            // the groups we need to place
-    g: usize,                       // the group we are currently placing
+    groups: &Vec<usize>,         // the groups we need to place
+    g: usize,                    // the group we are currently placing
     pos_in_string: usize, // the position in the string we are currently at (we move to the right)
-    dp: &mut HashMap<Memento, u64>, // intermediate results
+    dp: &mut Memo<Memento, u64>, // intermediate results
 ) -> u64 {
-    if let Some(count) = dp.get(&(g, pos_in_string)) {
-        return *count;
-    }
-
-    // cancel if we just skipped a spring
-    if (1..=line.len()).contains(&pos_in_string)
-        && line.as_bytes()[pos_in_string - 1] as char == SPRING
-    {
-        return 0;
-    } else if g == groups.len() {
-        // cancel if we left out a spring
-        if pos_in_string < line.len() && line[pos_in_string..].chars().any(|c| c == SPRING) {
+    dp.get_or_compute((g, pos_in_string), |dp, _| {
+        // cancel if we just skipped a spring
+        if (1..=line.len()).contains(&pos_in_string)
+            && line.as_bytes()[pos_in_string - 1] as char == SPRING
+        {
             return 0;
+        } else if g == groups.len() {
+            // cancel if we left out a spring
+            if pos_in_string < line.len() && line[pos_in_string..].chars().any(|c| c == SPRING) {
+                return 0;
+            }
+            // valid solution
+            return 1;
+        }
+
+        let mut count = 0;
+        let mut pos = pos_in_string;
+        while pos + groups[g] <= line.len() {
+            //grab next candidate
+            let candidate = &line[pos..pos + groups[g]];
+
+            // make sure we left no springs behind
+            if line[pos_in_string..pos].chars().any(|c| c == SPRING) {
+                // no need to try further
+                return count;
+            }
+
+            // lets see if candidate is valid (all ? or #)
+            if candidate.chars().all(|c| c != FREE) {
+                // lock this group (g+1) and continue search from right of our hit (pos + groups[g] + 1)
+                // +1 to leave room between groups
+                count += count_groups(line, groups, g + 1, pos + groups[g] + 1, dp);
+            }
+            pos += 1;
+        }
+        count
+    })
+}
+
+const UNKNOWN: char = '?';
+
+/// The number of arrangements of `springs` that match `groups`.
+fn count(springs: &str, groups: &Vec<usize>) -> u64 {
+    count_groups(springs, groups, 0, 0, &mut Memo::new())
+}
+
+impl Line {
+    /// The arrangements as filled-in rows without any `?`, leftmost groups first.
+    fn arrangements(&self, limit: Option<usize>) -> impl Iterator<Item = String> + '_ {
+        Arrangements::new(self).take(limit.unwrap_or(usize::MAX))
+    }
+}
+
+/// Lazily enumerates the arrangements of a [`Line`] by depth first search.
+///
+/// Every branch is checked with [`count_groups`] before it is taken, so the search never runs
+/// into a dead end and each row costs at most one pass over the line.
+struct Arrangements<'l> {
+    line: &'l Line,
+    dp: Memo<Memento, u64>,
+    /// the next group to place, where to continue in the line and the row so far
+    stack: Vec<(usize, usize, String)>,
+}
+
+impl<'l> Arrangements<'l> {
+    fn new(line: &'l Line) -> Self {
+        let mut dp = Memo::new();
+        let stack = if count_groups(&line.line, &line.groups, 0, 0, &mut dp) > 0 {
+            vec![(0, 0, String::new())]
+        } else {
+            vec![]
+        };
+        Self { line, dp, stack }
+    }
+}
+
+impl Iterator for Arrangements<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        let (line, groups) = (self.line.line.as_str(), &self.line.groups);
+        while let Some((g, start, row)) = self.stack.pop() {
+            if g == groups.len() {
+                let rest = line.len().saturating_sub(row.len());
+                return Some(row + &FREE.to_string().repeat(rest));
+            }
+
+            let size = groups[g];
+            let mut children = vec![];
+            let mut pos = start;
+            while pos + size <= line.len() && !line[start..pos].contains(SPRING) {
+                let end = pos + size;
+                if !line[pos..end].contains(FREE)
+                    && count_groups(line, groups, g + 1, end + 1, &mut self.dp) > 0
+                {
+                    let mut next = row.clone();
+                    next.extend(std::iter::repeat_n(FREE, pos - start));
+                    next.extend(std::iter::repeat_n(SPRING, size));
+                    if end < line.len() {
+                        next.push(FREE);
+                    }
+                    children.push((g + 1, end + 1, next));
+                }
+                pos += 1;
+            }
+            // leftmost placement on top
+            self.stack.extend(children.into_iter().rev());
+        }
+        None
+    }
+}
+
+/// A 2D nonogram, every row and column of the grid is a [`Line`] with its own groups.
+struct Nonogram {
+    rows: Vec<Vec<usize>>,
+    columns: Vec<Vec<usize>>,
+}
+
+impl Nonogram {
+    /// A filled-in grid that satisfies all rows and columns, None if there is none.
+    fn solve(&self) -> Option<Vec<String>> {
+        self.search(vec![vec![UNKNOWN; self.columns.len()]; self.rows.len()])
+    }
+
+    fn search(&self, mut grid: Vec<Vec<char>>) -> Option<Vec<String>> {
+        if !self.propagate(&mut grid) {
+            return None;
+        }
+        let Some((y, x)) = (0..grid.len())
+            .cartesian_product(0..self.columns.len())
+            .find(|&(y, x)| grid[y][x] == UNKNOWN)
+        else {
+            return Some(grid.iter().map(|row| row.iter().collect()).collect());
+        };
+        // guess, the propagation of the next level tells if it was wrong
+        [SPRING, FREE].into_iter().find_map(|guess| {
+            let mut grid = grid.clone();
+            grid[y][x] = guess;
+            self.search(grid)
+        })
+    }
+
+    /// Fixes every cell that has the same value in all arrangements of its row or column until
+    /// nothing changes any more, false on a contradiction.
+    fn propagate(&self, grid: &mut [Vec<char>]) -> bool {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (y, groups) in self.rows.iter().enumerate() {
+                let cells = grid[y].iter().collect::<String>();
+                let Some(solved) = solve_cells(&cells, groups) else {
+                    return false;
+                };
+                changed |= solved != cells;
+                grid[y] = solved.chars().collect();
+            }
+            for (x, groups) in self.columns.iter().enumerate() {
+                let cells = grid.iter().map(|row| row[x]).collect::<String>();
+                let Some(solved) = solve_cells(&cells, groups) else {
+                    return false;
+                };
+                changed |= solved != cells;
+                for (row, cell) in grid.iter_mut().zip(solved.chars()) {
+                    row[x] = cell;
+                }
+            }
         }
-        // valid solution
-        return 1;
+        true
     }
+}
 
-    let mut count = 0;
-    let mut pos = pos_in_string;
-    while pos + groups[g] <= line.len() {
-        //grab next candidate
-        let candidate = &line[pos..pos + groups[g]];
+/// Reads the row clues and then the column clues as two sections, one line of comma separated
+/// groups per row or column and `0` for an empty one.
+fn parse_nonogram(input: &str) -> Result<Nonogram, ParseError> {
+    let [rows, columns] = parse::sections_n(input)?;
+    let clues = |line| {
+        parse::integers(line)
+            .map(|groups: Vec<usize>| groups.into_iter().filter(|g| *g > 0).collect())
+    };
+    Ok(Nonogram {
+        rows: rows.parse_lines(clues)?,
+        columns: columns.parse_lines(clues)?,
+    })
+}
 
-        // make sure we left no springs behind
-        if line[pos_in_string..pos].chars().any(|c| c == SPRING) {
-            // no need to try further
-            return count;
+/// Fixes the unknown cells that can only be a spring or only be free, None if the cells have no
+/// arrangement at all.
+fn solve_cells(cells: &str, groups: &Vec<usize>) -> Option<String> {
+    if count(cells, groups) == 0 {
+        return None;
+    }
+    let mut solved = cells.to_string();
+    for (i, cell) in cells.char_indices() {
+        if cell != UNKNOWN {
+            continue;
+        }
+        let possible = |c: char| {
+            let mut candidate = cells.to_string();
+            candidate.replace_range(i..=i, &c.to_string());
+            count(&candidate, groups) > 0
+        };
+        match (possible(SPRING), possible(FREE)) {
+            (true, false) => solved.replace_range(i..=i, &SPRING.to_string()),
+            (false, true) => solved.replace_range(i..=i, &FREE.to_string()),
+            _ => {}
         }
+    }
+    Some(solved)
+}
 
-        // lets see if candidate is valid (all ? or #)
-        if candidate.chars().all(|c| c != FREE) {
-            // lock this group (g+1) and continue search from right of our hit (pos + groups[g] + 1)
-            count += count_groups(line, groups, g + 1, pos + groups[g] + 1, dp); // +1 to leave room between groups
-                                                                                 //dynamic programming
-            dp.insert((g, pos_in_string), count);
+/// Up to `limit` arrangements of every line of `input`, each line followed by its arrangements.
+pub fn list_arrangements(input: &str, limit: usize) -> Result<String, ParseError> {
+    let lines = parse::parse_lines(input, parse_line)?;
+    let mut out = String::new();
+    for line in &lines {
+        out += &format!("{} {}\n", line.line, line.groups.iter().join(","));
+        for arrangement in line.arrangements(Some(limit)) {
+            out += &format!("  {arrangement}\n");
         }
-        pos += 1;
     }
-    count
+    Ok(out)
 }
 
-pub fn part_one(input: &str) -> Option<u64> {
-    let lines = input.lines().map(parse_line).collect::<Vec<_>>();
-    let sum = lines
-        .iter()
-        .map(|l| count_groups(l.line.as_str(), &l.groups, 0, 0, &mut HashMap::new()))
-        .collect::<Vec<_>>();
+/// Solves the nonogram in `input`, see [`parse_nonogram`] for the format. The grid is None if
+/// the clues have no solution.
+pub fn solve_nonogram(input: &str) -> Result<Option<Vec<String>>, ParseError> {
+    parse_nonogram(input).map(|nonogram| nonogram.solve())
+}
 
-    Some(sum.iter().sum())
+pub fn part_one(input: &str) -> Option<u64> {
+    let lines = parse::parse_lines(input, parse_line).ok()?;
+    Some(count_lines(&lines, 1))
 }
 
-fn expand(line: &Line) -> Line {
-    let expanded_line = (0..5).map(|_| line.line.as_str()).join("?");
-    let expanded_group = (0..5)
+/// Repeats the springs `factor` times joined by `?` and the groups `factor` times.
+fn expand(line: &Line, factor: usize) -> Line {
+    let expanded_line = (0..factor).map(|_| line.line.as_str()).join("?");
+    let expanded_group = (0..factor)
         .flat_map(|_| line.groups.iter())
         .cloned()
         .collect::<Vec<_>>();
@@ -97,16 +284,79 @@ fn expand(line: &Line) -> Line {
     }
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    let expanded_lines = input.lines().map(parse_line).map(|it| expand(&it));
-    let sum = expanded_lines
-        .map(|l| count_groups(l.line.as_str(), &l.groups, 0, 0, &mut HashMap::new()))
+/// The number of arrangements of `springs` that match `groups`, by filling a table bottom up.
+///
+/// `ways[i]` is the number of arrangements of `springs[i..]` with the current group and all
+/// groups after it. The groups are added from last to first, so only the rows of the current
+/// and the next group are alive at a time.
+fn count_arrangements(springs: &[u8], groups: &[usize]) -> u64 {
+    let n = springs.len();
+    // free_before[i] is the number of `.` in springs[..i], to check a group fits in O(1)
+    let mut free_before = vec![0; n + 1];
+    for (i, &c) in springs.iter().enumerate() {
+        free_before[i + 1] = free_before[i] + usize::from(c == FREE as u8);
+    }
+
+    // without any groups left, only the positions after the last spring work
+    let last_spring = springs.iter().rposition(|&c| c == SPRING as u8);
+    let mut next = (0..=n)
+        .map(|i| u64::from(last_spring.is_none_or(|last| i > last)))
         .collect::<Vec<_>>();
-    Some(sum.iter().sum())
+    let mut ways = vec![0; n + 1];
+
+    for &size in groups.iter().rev() {
+        ways[n] = 0;
+        for i in (0..n).rev() {
+            // leave position `i` free
+            let mut count = if springs[i] == SPRING as u8 {
+                0
+            } else {
+                ways[i + 1]
+            };
+            // or start the group at `i`, followed by a free position or the end
+            let end = i + size;
+            if end <= n
+                && free_before[end] == free_before[i]
+                && springs.get(end) != Some(&(SPRING as u8))
+            {
+                count += next[(end + 1).min(n)];
+            }
+            ways[i] = count;
+        }
+        std::mem::swap(&mut ways, &mut next);
+    }
+    next[0]
+}
+
+/// Counts the arrangements of all lines expanded by `factor` in parallel.
+fn count_lines(lines: &[Line], factor: usize) -> u64 {
+    lines
+        .par_iter()
+        .map(|line| {
+            let line = expand(line, factor);
+            count_arrangements(line.line.as_bytes(), &line.groups)
+        })
+        .sum()
+}
+
+/// `--unfold <factor>` replaces the factor of 5.
+pub fn part_two(input: &str) -> Option<u64> {
+    let lines = parse::parse_lines(input, parse_line).ok()?;
+    let mut args = pico_args::Arguments::from_env();
+    let factor = match args.opt_value_from_str("--unfold") {
+        Ok(factor) => factor.unwrap_or(5),
+        Err(e) => {
+            eprintln!("Ignoring --unfold: {e}");
+            5
+        }
+    };
+    Some(count_lines(&lines, factor))
 }
 
 #[cfg(test)]
 mod tests {
+    use std::{hint::black_box, time::Instant};
+
     use super::*;
 
     #[test]
@@ -115,103 +365,242 @@ mod tests {
         assert_eq!(result, Some(21));
     }
 
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(parse_line("???.### 1,1,3").unwrap().groups, [1, 1, 3]);
+        let error = parse_line("???.### 1,x,3").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 11));
+        let error = parse_line("???.### 1,99999999999999999999999").unwrap_err();
+        assert_eq!(error.column, 11);
+        assert_eq!(part_one("???.### 1,x,3"), None);
+    }
+
     #[test]
     fn test_line_1() {
-        let line = parse_line("???.### 1,1,3");
+        let line = parse_line("???.### 1,1,3").unwrap();
         assert_eq!(
-            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut HashMap::new()),
+            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut Memo::new()),
             1
         );
     }
 
     #[test]
     fn test_line_2() {
-        let line = parse_line(".??..??...?##. 1,1,3");
+        let line = parse_line(".??..??...?##. 1,1,3").unwrap();
         assert_eq!(
-            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut HashMap::new()),
+            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut Memo::new()),
             4
         );
     }
 
     #[test]
     fn test_line_3() {
-        let line = parse_line("?#?#?#?#?#?#?#? 1,3,1,6");
+        let line = parse_line("?#?#?#?#?#?#?#? 1,3,1,6").unwrap();
         assert_eq!(
-            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut HashMap::new()),
+            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut Memo::new()),
             1
         );
     }
 
     #[test]
     fn test_line_4() {
-        let line = parse_line("????.#...#... 4,1,1");
+        let line = parse_line("????.#...#... 4,1,1").unwrap();
         assert_eq!(
-            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut HashMap::new()),
+            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut Memo::new()),
             1
         );
     }
 
     #[test]
     fn test_line_5() {
-        let line = parse_line("????.######..#####. 1,6,5");
+        let line = parse_line("????.######..#####. 1,6,5").unwrap();
         assert_eq!(
-            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut HashMap::new()),
+            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut Memo::new()),
             4
         );
     }
 
     #[test]
     fn test_line_6() {
-        let line = parse_line("?###???????? 3,2,1");
+        let line = parse_line("?###???????? 3,2,1").unwrap();
         assert_eq!(
-            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut HashMap::new()),
+            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut Memo::new()),
             10
         );
     }
 
     #[test]
     fn test_line_7() {
-        let line = parse_line("?.?#????#??## 1,2,7");
+        let line = parse_line("?.?#????#??## 1,2,7").unwrap();
         assert_eq!(
-            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut HashMap::new()),
+            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut Memo::new()),
             2
         );
     }
 
     #[test]
     fn test_b_() {
-        let line = expand(&parse_line("???.### 1,1,3"));
+        let line = expand(&parse_line("???.### 1,1,3").unwrap(), 5);
         assert_eq!(
-            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut HashMap::new()),
+            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut Memo::new()),
             1
         );
-        let line = expand(&parse_line(".??..??...?##. 1,1,3"));
+        let line = expand(&parse_line(".??..??...?##. 1,1,3").unwrap(), 5);
         assert_eq!(
-            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut HashMap::new()),
+            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut Memo::new()),
             16384
         );
-        let line = expand(&parse_line("?#?#?#?#?#?#?#? 1,3,1,6"));
+        let line = expand(&parse_line("?#?#?#?#?#?#?#? 1,3,1,6").unwrap(), 5);
         assert_eq!(
-            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut HashMap::new()),
+            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut Memo::new()),
             1
         );
-        let line = expand(&parse_line("????.#...#... 4,1,1"));
+        let line = expand(&parse_line("????.#...#... 4,1,1").unwrap(), 5);
         assert_eq!(
-            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut HashMap::new()),
+            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut Memo::new()),
             16
         );
-        let line = expand(&parse_line("????.######..#####. 1,6,5"));
+        let line = expand(&parse_line("????.######..#####. 1,6,5").unwrap(), 5);
         assert_eq!(
-            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut HashMap::new()),
+            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut Memo::new()),
             2500
         );
-        let line = expand(&parse_line("?###???????? 3,2,1"));
+        let line = expand(&parse_line("?###???????? 3,2,1").unwrap(), 5);
         assert_eq!(
-            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut HashMap::new()),
+            count_groups(line.line.as_str(), &line.groups, 0, 0, &mut Memo::new()),
             506250
         );
     }
 
+    #[test]
+    fn test_arrangements() {
+        let line = parse_line(".??..??...?##. 1,1,3").unwrap();
+        assert_eq!(
+            line.arrangements(None).collect_vec(),
+            [
+                ".#...#....###.",
+                ".#....#...###.",
+                "..#..#....###.",
+                "..#...#...###.",
+            ]
+        );
+        assert_eq!(line.arrangements(Some(2)).count(), 2);
+
+        let line = parse_line("?###???????? 3,2,1").unwrap();
+        let arrangements = line.arrangements(None).collect_vec();
+        assert_eq!(arrangements.len(), 10);
+        assert_eq!(arrangements[0], ".###.##.#...");
+        assert!(arrangements.iter().all_unique());
+
+        // lazy, the expanded line has 506250 arrangements
+        let line = expand(&line, 5);
+        assert_eq!(line.arrangements(Some(3)).count(), 3);
+
+        let impossible = parse_line("#.# 3").unwrap();
+        assert_eq!(impossible.arrangements(None).next(), None);
+
+        assert_eq!(
+            list_arrangements("???.### 1,1,3\n#.# 3\n.??. 1\n", 1),
+            Ok("???.### 1,1,3\n  #.#.###\n#.# 3\n.??. 1\n  .#..\n".to_string())
+        );
+        assert!(list_arrangements("???.###", 1).is_err());
+    }
+
+    #[test]
+    fn test_nonogram() {
+        let heart = Nonogram {
+            rows: vec![vec![1, 1], vec![5], vec![5], vec![3], vec![1]],
+            columns: vec![vec![2], vec![4], vec![4], vec![4], vec![2]],
+        };
+        assert_eq!(
+            heart.solve().unwrap(),
+            [".#.#.", "#####", "#####", ".###.", "..#.."]
+        );
+
+        // needs guessing, both diagonals fit the clues
+        let diagonal = Nonogram {
+            rows: vec![vec![1], vec![1]],
+            columns: vec![vec![1], vec![1]],
+        };
+        assert_eq!(diagonal.solve().unwrap(), ["#.", ".#"]);
+
+        let solved = solve_nonogram("1,1\n5\n5\n3\n1\n\n2\n4\n4\n4\n2\n");
+        assert_eq!(solved, Ok(heart.solve()));
+        assert_eq!(solve_nonogram("2\n\n1\n0\n"), Ok(None));
+        let empty_column = parse_nonogram("1\n\n0\n1\n").unwrap();
+        assert_eq!(empty_column.columns, [vec![], vec![1]]);
+        assert!(parse_nonogram("1\n").is_err());
+
+        let impossible = Nonogram {
+            rows: vec![vec![2]],
+            columns: vec![vec![1], vec![]],
+        };
+        assert_eq!(impossible.solve(), None);
+    }
+
+    #[test]
+    fn test_table() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let lines = parse::parse_lines(&input, parse_line).unwrap();
+        for factor in 1..=5 {
+            for line in lines.iter().map(|line| expand(line, factor)) {
+                assert_eq!(
+                    count_arrangements(line.line.as_bytes(), &line.groups),
+                    count(&line.line, &line.groups),
+                    "{} {:?}",
+                    line.line,
+                    line.groups
+                );
+            }
+        }
+        assert_eq!(count_lines(&lines, 1), 21);
+        assert_eq!(count_arrangements(b"", &[]), 1);
+        assert_eq!(count_arrangements(b"#", &[]), 0);
+        assert_eq!(count_arrangements(b"?", &[2]), 0);
+        assert_eq!(count_arrangements(b"##", &[2]), 1);
+    }
+
+    /// counts the arrangements of all lines, reusing one memo that is cleared between lines
+    fn count_all(lines: impl Iterator<Item = Line>) -> u64 {
+        let mut dp = Memo::new();
+        lines
+            .map(|l| {
+                dp.clear();
+                count_groups(l.line.as_str(), &l.groups, 0, 0, &mut dp)
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_compare() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let lines = parse::parse_lines(&input, parse_line).unwrap();
+        for factor in 1..=5 {
+            let recursive = count_all(lines.iter().map(|line| expand(line, factor)));
+            assert_eq!(recursive, count_lines(&lines, factor), "{factor}");
+        }
+    }
+
+    /// Times the table against the recursive solution on the real input.
+    #[test]
+    #[ignore = "benchmark, run with `cargo test --release --bin 12 -- --ignored --nocapture`"]
+    fn bench_compare() {
+        let input = advent_of_code::template::read_file("inputs", DAY);
+        let lines = parse::parse_lines(&input, parse_line).unwrap();
+        let samples = 10;
+        let time = |name: &str, count: &dyn Fn() -> u64| {
+            let timer = Instant::now();
+            let result = (0..samples).map(|_| black_box(count())).last().unwrap();
+            println!("{name}: {result} in {:.1?}", timer.elapsed() / samples);
+            result
+        };
+        let recursive = time("recursive", &|| {
+            count_all(lines.iter().map(|line| expand(line, 5)))
+        });
+        let table = time("table", &|| count_lines(&lines, 5));
+        assert_eq!(recursive, table);
+    }
+
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
@@ -1,151 +1,194 @@
-use core::panic;
+use std::fmt::Display;
 
-advent_of_code::solution!(13);
+use advent_of_code::parse::{self, ParseError};
+use itertools::Itertools;
 
-const JOKER: u8 = 0;
+advent_of_code::solution!(13, extras = extras);
 
-struct Map {
-    bytes: Vec<Vec<u8>>,
-    transposed: bool,
+type Cell = (usize, usize);
+
+/// A line of reflection, by the number of columns left or rows above it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Axis {
+    Vertical(usize),
+    Horizontal(usize),
 }
 
-impl Map {
-    fn new(str: &str, transposed: bool) -> Map {
-        let bytes = str.lines().map(|l| l.as_bytes().to_vec()).collect();
-        Map { bytes, transposed }
+impl Axis {
+    fn summary(&self) -> u32 {
+        match self {
+            Axis::Vertical(columns) => *columns as u32,
+            Axis::Horizontal(rows) => 100 * *rows as u32,
+        }
     }
+}
 
-    fn get(&self, row: i32, col: i32) -> u8 {
-        if self.transposed {
-            self.do_get(col, row)
-        } else {
-            self.do_get(row, col)
+impl Display for Axis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Axis::Vertical(columns) => write!(f, "vertical line after column {columns}"),
+            Axis::Horizontal(rows) => write!(f, "horizontal line after row {rows}"),
         }
     }
+}
 
-    fn get_width(&self) -> usize {
-        if self.transposed {
-            self.bytes.len()
-        } else {
-            self.bytes[0].len()
-        }
+/// An axis with the pairs of mirrored cells that differ, fixing either cell of a pair removes
+/// that smudge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Reflection {
+    axis: Axis,
+    smudges: Vec<(Cell, Cell)>,
+}
+
+struct Pattern {
+    rows: Vec<Vec<u8>>,
+}
+
+impl Pattern {
+    /// Reads the rows of a pattern, all of them as wide as the first one.
+    fn new(str: &str) -> Result<Pattern, ParseError> {
+        let width = str.lines().next().map_or(0, str::len);
+        let rows = parse::parse_lines(str, |line| {
+            if line.len() == width {
+                Ok(line.as_bytes().to_vec())
+            } else {
+                let message = format!(
+                    "expected {width} cells like the first row, found {}",
+                    line.len()
+                );
+                Err(ParseError::at(line, line, message))
+            }
+        })?;
+        Ok(Pattern { rows })
     }
 
-    fn get_height(&self) -> usize {
-        if self.transposed {
-            self.bytes[0].len()
-        } else {
-            self.bytes.len()
-        }
+    fn width(&self) -> usize {
+        self.rows.first().map_or(0, Vec::len)
     }
 
-    fn do_get(&self, row: i32, col: i32) -> u8 {
-        if col < 0 || col >= self.bytes[0].len() as i32 || row < 0 || row >= self.bytes.len() as i32
-        {
-            JOKER
-        } else {
-            self.bytes[row as usize][col as usize]
-        }
+    fn height(&self) -> usize {
+        self.rows.len()
     }
 
-    fn test(&self, left_start: usize) -> bool {
-        if left_start >= self.get_width() - 1 {
-            return false;
-        }
+    /// All axes between two columns or two rows, vertical ones first.
+    fn axes(&self) -> impl Iterator<Item = Axis> {
+        (1..self.width())
+            .map(Axis::Vertical)
+            .chain((1..self.height()).map(Axis::Horizontal))
+    }
 
-        for row in 0..self.get_height() {
-            let mut left = left_start as i32;
-            let mut right = left_start as i32 + 1;
-            while left >= 0 && right < self.get_width() as i32 {
-                let left_value = self.get(row as i32, left);
-                let right_value = self.get(row as i32, right);
-
-                if !(left_value == JOKER || right_value == JOKER || left_value == right_value) {
-                    return false;
-                }
-                left -= 1;
-                right += 1;
-            }
-        }
-        true
+    /// The cells that differ from their mirror image across `axis`, each with its mirror image,
+    /// cells on the left or top side first.
+    fn mismatches(&self, axis: Axis) -> impl Iterator<Item = (Cell, Cell)> + '_ {
+        let (width, height) = (self.width(), self.height());
+        let pairs: Box<dyn Iterator<Item = (Cell, Cell)>> = match axis {
+            Axis::Vertical(columns) => Box::new(
+                (0..height)
+                    .cartesian_product(0..columns.min(width - columns))
+                    .map(move |(row, d)| ((row, columns - 1 - d), (row, columns + d))),
+            ),
+            Axis::Horizontal(rows) => Box::new(
+                (0..rows.min(height - rows))
+                    .cartesian_product(0..width)
+                    .map(move |(d, column)| ((rows - 1 - d, column), (rows + d, column))),
+            ),
+        };
+        pairs.filter(|((r1, c1), (r2, c2))| self.rows[*r1][*c1] != self.rows[*r2][*c2])
     }
 
-    fn flip_abs(&mut self, row: usize, col: usize) {
-        let value = self.bytes[row][col];
-        if value == b'.' {
-            self.bytes[row][col] = b'#';
-        } else if value == b'#' {
-            self.bytes[row][col] = b'.';
-        } else {
-            panic!("invalid value: {}", value);
+    /// The first axis with exactly `smudges` mismatching cells.
+    fn find_reflection(&self, smudges: usize) -> Option<Reflection> {
+        self.axes().find_map(|axis| {
+            // one more than needed is enough to tell
+            let found = self.mismatches(axis).take(smudges + 1).collect_vec();
+            (found.len() == smudges).then_some(Reflection {
+                axis,
+                smudges: found,
+            })
+        })
+    }
+}
+
+fn parse_patterns(input: &str) -> Result<Vec<Pattern>, ParseError> {
+    parse::sections(input)
+        .iter()
+        .map(|section| Pattern::new(section.text).map_err(|e| e.shifted(section.line_offset)))
+        .collect()
+}
+
+/// The summary of all patterns with exactly `smudges` smudges each, None if a pattern has no
+/// such reflection.
+fn solve(input: &str, smudges: usize) -> Option<u32> {
+    let patterns = match parse_patterns(input) {
+        Ok(patterns) => patterns,
+        Err(e) => {
+            eprintln!("{e}");
+            return None;
         }
+    };
+    patterns
+        .iter()
+        .map(|pattern| pattern.find_reflection(smudges).map(|r| r.axis.summary()))
+        .sum()
+}
+
+fn print_reflection(pattern: usize, reflection: Option<&Reflection>) {
+    let Some(reflection) = reflection else {
+        return println!("Pattern {pattern}: no reflection");
+    };
+    let smudges = reflection
+        .smudges
+        .iter()
+        .map(|((r1, c1), (r2, c2))| format!("({}, {}) or ({}, {})", r1 + 1, c1 + 1, r2 + 1, c2 + 1))
+        .join(", ");
+    if smudges.is_empty() {
+        println!("Pattern {pattern}: {}", reflection.axis);
+    } else {
+        println!(
+            "Pattern {pattern}: {}, smudged at {smudges}",
+            reflection.axis
+        );
     }
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let sum = input
-        .split("\n\n")
-        .map(|it| (Map::new(it, false), Map::new(it, true)))
-        .map(|(lines, cols)| {
-            for l in 0..lines.get_width() {
-                if lines.test(l) {
-                    return (l + 1) as u32;
-                }
-            }
-            for l in 0..cols.get_width() {
-                if cols.test(l) {
-                    return (100 * (l + 1)) as u32;
-                }
-            }
-            panic!(
-                "no solution found:\n{}",
-                lines
-                    .bytes
-                    .iter()
-                    .map(|it| String::from_utf8(it.clone()).unwrap())
-                    .collect::<Vec<String>>()
-                    .join("\n")
-            );
-        })
-        .sum();
-    Some(sum)
+    solve(input, 0)
+}
+
+/// `--smudges <k>` looks for `k` smudges per pattern instead of one.
+fn smudges_option() -> usize {
+    let mut args = pico_args::Arguments::from_env();
+    match args.opt_value_from_str("--smudges") {
+        Ok(smudges) => smudges.unwrap_or(1),
+        Err(e) => {
+            eprintln!("Ignoring --smudges: {e}");
+            1
+        }
+    }
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let sum = input
-        .split("\n\n")
-        .map(|it| (Map::new(it, false), Map::new(it, true)))
-        .map(|(mut lines, mut map)| {
-            for x in 0..lines.get_width() {
-                for y in 0..lines.get_height() {
-                    lines.flip_abs(y, x);
-                    for l in 0..lines.get_width() {
-                        if lines.test(l) {
-                            return (l + 1) as u32;
-                        }
-                    }
-                    lines.flip_abs(y, x);
-
-                    map.flip_abs(y, x);
-                    for l in 0..map.get_width() {
-                        if map.test(l) {
-                            return (100 * (l + 1)) as u32;
-                        }
-                    }
-                    map.transposed = false;
-                    map.flip_abs(x, y);
-                }
-            }
-            unreachable!()
-        })
-        .sum();
-    Some(sum)
+    solve(input, smudges_option())
+}
+
+/// `--reflections` prints the axis and the smudges of every pattern for both parts.
+fn extras(input: &str) {
+    if !std::env::args().any(|arg| arg == "--reflections") {
+        return;
+    }
+    let Ok(patterns) = parse_patterns(input) else {
+        return;
+    };
+    for (part, smudges) in [(1, 0), (2, smudges_option())] {
+        println!("Part {part}:");
+        for (i, pattern) in patterns.iter().enumerate() {
+            print_reflection(i + 1, pattern.find_reflection(smudges).as_ref());
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::iter;
-
     use super::*;
 
     #[test]
@@ -158,27 +201,25 @@ mod tests {
 ..##..##.
 #.#.##.#."#;
 
-        let map = Map::new(str, false);
-        vec![
-            (0, false),
-            (1, false),
-            (2, false),
-            (3, false),
-            (4, true),
-            (5, false),
-            (6, false),
-            (7, false),
-            (8, false),
-            (9, false),
-        ]
-        .iter()
-        .for_each(|(i, expected)| assert_eq!(*expected, map.test(*i)))
+        let pattern = Pattern::new(str).unwrap();
+        (1..9)
+            .map(|columns| (columns, columns == 5))
+            .for_each(|(columns, expected)| {
+                let mut mismatches = pattern.mismatches(Axis::Vertical(columns));
+                assert_eq!(expected, mismatches.next().is_none(), "{columns}");
+            });
+        assert_eq!(
+            pattern.find_reflection(1),
+            Some(Reflection {
+                axis: Axis::Horizontal(3),
+                smudges: vec![((0, 0), (5, 0))]
+            })
+        );
     }
 
     #[test]
     fn test_bigger_map() {
-        let str = r#"
-#.#...##..#
+        let str = r#"#.#...##..#
 ####..##.#.
 #..#.......
 #..#.......
@@ -194,8 +235,30 @@ mod tests {
 .##.##.##.#
 .##.##.##.#"#;
 
-        let map = Map::new(str, true);
-        assert!(map.test(2))
+        let pattern = Pattern::new(str).unwrap();
+        assert_eq!(
+            pattern.find_reflection(0).map(|r| r.axis),
+            Some(Axis::Horizontal(14))
+        );
+        assert_eq!(
+            pattern.find_reflection(1),
+            Some(Reflection {
+                axis: Axis::Horizontal(3),
+                smudges: vec![((0, 4), (5, 4))]
+            })
+        );
+    }
+
+    #[test]
+    fn test_no_reflection() {
+        let pattern = Pattern::new("#.\n..").unwrap();
+        assert_eq!(pattern.find_reflection(0), None);
+        assert_eq!(
+            pattern.find_reflection(1).map(|r| r.axis),
+            Some(Axis::Vertical(1))
+        );
+        assert_eq!(solve("#.\n..\n\n##\n..", 0), None);
+        assert_eq!(solve("##\n..", 0), Some(1));
     }
 
     #[test]
@@ -209,4 +272,37 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(400));
     }
+
+    #[test]
+    fn test_more_smudges() {
+        // the axes after columns 1 to 5 have 1, 2, 2, 1 and 0 mismatching cells
+        let pattern = Pattern::new("#.#...").unwrap();
+        let axes = (0..4)
+            .map(|smudges| pattern.find_reflection(smudges).map(|r| r.axis))
+            .collect_vec();
+        assert_eq!(
+            axes,
+            [
+                Some(Axis::Vertical(5)),
+                Some(Axis::Vertical(1)),
+                Some(Axis::Vertical(2)),
+                None
+            ]
+        );
+        assert_eq!(
+            pattern.find_reflection(2).unwrap().smudges,
+            [((0, 1), (0, 2)), ((0, 0), (0, 3))]
+        );
+        assert_eq!(solve("#.#...", 2), Some(2));
+    }
+
+    #[test]
+    fn test_ragged_pattern() {
+        let error = Pattern::new("#.#\n#.\n#.#").err().unwrap();
+        assert_eq!((error.line, error.column), (2, 1));
+        let error = Pattern::new("#.\n#..").err().unwrap();
+        assert_eq!(error.line, 2);
+        // one ragged pattern spoils the whole input
+        assert_eq!(solve("##\n..\n\n#.\n#..", 0), None);
+    }
 }
@@ -1,16 +1,17 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt::Display};
 
-advent_of_code::solution!(14);
+advent_of_code::solution!(14, extras = extras);
 
 const LOOSE: u8 = b'O';
 const FREE: u8 = b'.';
+const CUBE: u8 = b'#';
 
 struct Platform {
     bytes: Vec<Vec<u8>>,
     pull: Orientation,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 enum Orientation {
     Up,
     Left,
@@ -18,6 +19,29 @@ enum Orientation {
     Down,
 }
 
+impl Display for Orientation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Orientation::Up => "north",
+            Orientation::Left => "west",
+            Orientation::Right => "east",
+            Orientation::Down => "south",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The positions of the loose rocks as bitset, row by row, enough to tell two states of the same
+/// platform apart.
+type State = Vec<u64>;
+
+const SPIN_CYCLE: [Orientation; 4] = [
+    Orientation::Up,
+    Orientation::Left,
+    Orientation::Down,
+    Orientation::Right,
+];
+
 impl Platform {
     fn new(str: &str) -> Platform {
         let bytes = str.lines().map(|l| l.as_bytes().to_vec()).collect();
@@ -27,69 +51,77 @@ impl Platform {
         }
     }
 
-    fn get_width(&self) -> i32 {
-        self.bytes[0].len() as i32
+    fn get_width(&self) -> usize {
+        self.bytes[0].len()
     }
 
-    fn get_height(&self) -> i32 {
-        self.bytes.len() as i32
+    fn get_height(&self) -> usize {
+        self.bytes.len()
     }
 
-    fn translate(&self, x: i32, y: i32) -> (i32, i32) {
-        let w = self.get_width();
-        let h = self.get_height();
+    /// The `i`th position of the `line`th column or row, counted from the side of the pull.
+    fn position(&self, line: usize, i: usize) -> (usize, usize) {
         match self.pull {
-            Orientation::Up => (x, y),
-            Orientation::Left => (y, w - x - 1),
-            Orientation::Down => (x, h - y - 1),
-            Orientation::Right => (h - y - 1, x),
+            Orientation::Up => (line, i),
+            Orientation::Down => (line, self.get_height() - i - 1),
+            Orientation::Left => (i, line),
+            Orientation::Right => (self.get_width() - i - 1, line),
         }
     }
 
     fn full_rotate(&mut self) {
-        let sequence = vec![
-            Orientation::Up,
-            Orientation::Left,
-            Orientation::Down,
-            Orientation::Right,
-        ];
-        sequence.iter().for_each(|p| {
+        self.full_rotate_with(|_| {});
+    }
+
+    /// Runs one spin cycle and calls `tilted` after each tilt.
+    fn full_rotate_with(&mut self, mut tilted: impl FnMut(&Platform)) {
+        SPIN_CYCLE.iter().for_each(|p| {
             self.pull = *p;
             self.gravity();
+            tilted(self);
         });
     }
 
-    fn get(&self, x: i32, y: i32) -> u8 {
-        let (x, y) = self.translate(x, y);
-        if x < 0 || y < 0 || x >= self.get_width() || y >= self.get_height() {
-            return 0;
+    /// Moves the loose rocks of every column or row towards the pull in one pass: each rock
+    /// goes to the first free position after the last cube rock or rock it met.
+    fn gravity(&mut self) {
+        let (lines, length) = match self.pull {
+            Orientation::Up | Orientation::Down => (self.get_width(), self.get_height()),
+            Orientation::Left | Orientation::Right => (self.get_height(), self.get_width()),
+        };
+        for line in 0..lines {
+            let mut free = 0;
+            for i in 0..length {
+                let (x, y) = self.position(line, i);
+                match self.bytes[y][x] {
+                    CUBE => free = i + 1,
+                    LOOSE => {
+                        self.bytes[y][x] = FREE;
+                        let (x, y) = self.position(line, free);
+                        self.bytes[y][x] = LOOSE;
+                        free += 1;
+                    }
+                    _ => {}
+                }
+            }
         }
-        self.bytes[y as usize][x as usize]
     }
 
-    fn set(&mut self, x: i32, y: i32, val: u8) {
-        let (x, y) = self.translate(x, y);
-        self.bytes[y as usize][x as usize] = val;
-    }
-
-    fn gravity(&mut self) {
-        (0..self.get_height()).for_each(|row| {
-            (0..self.get_width()).for_each(|x| {
-                let mut y = row;
-                while y >= 0 && self.get(x, y) == LOOSE && self.get(x, y - 1) == FREE {
-                    self.set(x, y, FREE);
-                    y -= 1;
-                    self.set(x, y, LOOSE);
-                }
-            })
-        })
+    fn state(&self) -> State {
+        let mut state = vec![0u64; (self.get_width() * self.get_height()).div_ceil(64)];
+        for (i, byte) in self.bytes.iter().flatten().enumerate() {
+            if *byte == LOOSE {
+                state[i / 64] |= 1 << (i % 64);
+            }
+        }
+        state
     }
 
     fn get_score(&self) -> u32 {
         let mut score = 0u32;
         (0..self.get_height()).for_each(|y| {
             (0..self.get_width()).for_each(|x| {
-                if self.bytes[y as usize][x as usize] == LOOSE {
+                if self.bytes[y][x] == LOOSE {
                     score += (self.get_height() - y) as u32;
                 }
             });
@@ -98,13 +130,14 @@ impl Platform {
     }
 }
 
-impl ToString for Platform {
-    fn to_string(&self) -> String {
-        self.bytes
+impl Display for Platform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rows = self
+            .bytes
             .iter()
-            .map(|l| String::from_utf8(l.clone()).unwrap())
-            .collect::<Vec<String>>()
-            .join("\n")
+            .map(|l| String::from_utf8_lossy(l))
+            .collect::<Vec<_>>();
+        write!(f, "{}", rows.join("\n"))
     }
 }
 
@@ -114,22 +147,38 @@ pub fn part_one(input: &str) -> Option<u32> {
     Some(platform.get_score())
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let mut platform = Platform::new(input);
+/// The platform after every tilt of the spin cycles until the states repeat, with headers like
+/// `Cycle 1, tilted north:`.
+fn record_frames(platform: &mut Platform, cycles: usize) -> String {
+    let mut frames = format!("Initial:\n{platform}\n");
+    for cycle in 1..=cycles {
+        platform.full_rotate_with(|platform| {
+            frames += &format!("\nCycle {cycle}, tilted {}:\n{platform}\n", platform.pull);
+        });
+    }
+    frames
+}
 
+/// Spins `platform` until a state repeats, returns the cycle the loop starts and ends with.
+fn find_loop(platform: &mut Platform) -> (usize, usize) {
     let mut memento = HashMap::new();
     let mut loop_end = 0;
     let mut loop_start = None;
 
     // look for a loop and record the start and the end of the loop
     while loop_start.is_none() {
-        loop_start = memento.insert(platform.to_string(), loop_end);
+        loop_start = memento.insert(platform.state(), loop_end);
         if loop_start.is_none() {
             loop_end += 1;
             platform.full_rotate();
         }
     }
-    let loop_start = loop_start.unwrap();
+    (loop_start.unwrap(), loop_end)
+}
+
+pub fn part_two(input: &str) -> Option<u32> {
+    let mut platform = Platform::new(input);
+    let (loop_start, loop_end) = find_loop(&mut platform);
     let repeat = loop_end - loop_start;
 
     // from [loop_start] on, we see the same result every [repeat] times
@@ -141,6 +190,23 @@ pub fn part_two(input: &str) -> Option<u32> {
     Some(platform.get_score())
 }
 
+/// `--frames <file>` writes every tilt until the platform repeats into `file`.
+fn extras(input: &str) {
+    let mut args = pico_args::Arguments::from_env();
+    let Ok(Some(file)) = args.opt_value_from_str::<_, String>("--frames") else {
+        return;
+    };
+    let (loop_start, loop_end) = find_loop(&mut Platform::new(input));
+    let frames = record_frames(&mut Platform::new(input), loop_end);
+    match std::fs::write(&file, frames) {
+        Ok(_) => println!(
+            "Wrote {} frames to {file}, cycle {loop_end} repeats cycle {loop_start}",
+            4 * loop_end + 1
+        ),
+        Err(e) => eprintln!("Failed to write frames to {file}: {e}"),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -312,6 +378,50 @@ O.#..O.#.#
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn test_state() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let mut platform = Platform::new(&input);
+        let initial = platform.state();
+        assert_eq!(initial.len(), 2);
+        assert_eq!(
+            initial.iter().map(|s| s.count_ones()).sum::<u32>(),
+            input.matches('O').count() as u32
+        );
+        platform.full_rotate();
+        assert_ne!(platform.state(), initial);
+        // the example repeats every 7 cycles from the third one on
+        (1..3).for_each(|_| platform.full_rotate());
+        let third = platform.state();
+        (0..6).for_each(|_| platform.full_rotate());
+        assert_ne!(platform.state(), third);
+        platform.full_rotate();
+        assert_eq!(platform.state(), third);
+    }
+
+    #[test]
+    fn test_frames() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let frames = record_frames(&mut Platform::new(&input), 1);
+        let expected = format!("Initial:\n{}\n\nCycle 1, tilted north:\n", input.trim_end());
+        assert!(frames.starts_with(&expected));
+        assert_eq!(frames.matches("Cycle 1, tilted").count(), 4);
+        assert!(frames.ends_with(
+            "Cycle 1, tilted east:
+.....#....
+....#...O#
+...OO##...
+.OO#......
+.....OOO#.
+.O#...O#.#
+....O#....
+......OOOO
+#...O###..
+#..OO#....
+"
+        ));
+    }
+
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
@@ -1,4 +1,10 @@
-advent_of_code::solution!(15);
+use std::fmt::Display;
+
+use advent_of_code::parse::{self, ParseError};
+use itertools::Itertools;
+use linked_hash_map::LinkedHashMap;
+
+advent_of_code::solution!(15, extras = extras);
 
 pub fn part_one(input: &str) -> Option<u32> {
     Some(
@@ -11,69 +17,155 @@ pub fn part_one(input: &str) -> Option<u32> {
     )
 }
 
-#[derive(Debug, Default)]
-struct LenseBox {
-    lenses: Vec<(String, u32)>,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operation {
+    Remove,
+    Insert(u32),
 }
 
-impl LenseBox {
-    fn add(&mut self, name: &str, focal_len: u32) {
-        for i in &mut self.lenses {
-            if i.0 == name {
-                i.1 = focal_len;
-                return;
-            }
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Step {
+    label: String,
+    operation: Operation,
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.operation {
+            Operation::Remove => write!(f, "{}-", self.label),
+            Operation::Insert(focal_len) => write!(f, "{}={focal_len}", self.label),
         }
-        self.lenses.push((name.to_string(), focal_len));
     }
+}
 
-    fn remove(&mut self, name: &str) {
-        self.lenses.retain(|it| it.0 != name);
+fn parse_step(text: &str) -> Result<Step, ParseError> {
+    let text = text.trim();
+    let (label, operation) = if let Some(label) = text.strip_suffix('-') {
+        (label, Operation::Remove)
+    } else {
+        let (label, focal_len) = parse::split_once(text, "=")?;
+        (label, Operation::Insert(parse::value(text, focal_len)?))
+    };
+    if label.is_empty() {
+        return Err(ParseError::at(text, text, "missing label"));
     }
+    Ok(Step {
+        label: label.to_string(),
+        operation,
+    })
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let mut lenses = (0..256).map(|_| LenseBox::default()).collect::<Vec<_>>();
-
-    let binding = input.replace('\n', "");
-    let commands = binding
-        .split(',')
-        .map(|it| it.split_once(['=', '-']).unwrap())
-        .map(|(cmd, args)| (cmd.trim(), args.trim().parse::<u32>().ok()))
-        .collect::<Vec<_>>();
-
-    for (cmd, arg) in commands {
-        let lense_box = &mut lenses.get_mut(hash(cmd) as usize).unwrap();
-        if let Some(arg) = arg {
-            //add
-            lense_box.add(cmd, arg);
-        } else {
-            //remove
-            lense_box.remove(cmd);
+fn parse_steps(input: &str) -> Result<Vec<Step>, ParseError> {
+    input.replace('\n', "").split(',').map(parse_step).collect()
+}
+
+/// The 256 boxes of the HASHMAP procedure.
+///
+/// Every box keeps its lenses in insertion order with a label index, so inserting, replacing and
+/// removing a lens are all O(1).
+struct Hashmap256 {
+    boxes: Vec<LinkedHashMap<String, u32>>,
+}
+
+impl Hashmap256 {
+    fn new() -> Self {
+        Self {
+            boxes: (0..256).map(|_| LinkedHashMap::new()).collect(),
+        }
+    }
+
+    /// Puts the lens behind the others in its box or replaces the lens with the same label in
+    /// place.
+    fn insert(&mut self, label: &str, focal_len: u32) {
+        let lenses = &mut self.boxes[hash(label) as usize];
+        match lenses.get_mut(label) {
+            Some(lens) => *lens = focal_len,
+            None => {
+                lenses.insert(label.to_string(), focal_len);
+            }
         }
     }
 
-    let mut sum = 0;
-    for (box_no, lense_box) in lenses.iter().enumerate() {
-        for (slot_no, (_, focal_len)) in lense_box.lenses.iter().enumerate() {
-            sum += (box_no as u32 + 1) * (slot_no as u32 + 1) * focal_len;
+    /// Takes the lens out of its box, returns its focal length if there was one.
+    fn remove(&mut self, label: &str) -> Option<u32> {
+        self.boxes[hash(label) as usize].remove(label)
+    }
+
+    fn apply(&mut self, step: &Step) {
+        match step.operation {
+            Operation::Remove => {
+                self.remove(&step.label);
+            }
+            Operation::Insert(focal_len) => self.insert(&step.label, focal_len),
         }
     }
 
-    Some(sum)
+    fn focusing_power(&self) -> u32 {
+        let mut sum = 0;
+        for (box_no, lenses) in self.boxes.iter().enumerate() {
+            for (slot_no, focal_len) in lenses.values().enumerate() {
+                sum += (box_no as u32 + 1) * (slot_no as u32 + 1) * focal_len;
+            }
+        }
+        sum
+    }
+}
+
+impl Display for Hashmap256 {
+    /// The boxes with lenses like the puzzle shows them, e.g. `Box 0: [rn 1] [cm 2]`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (box_no, lenses) in self.boxes.iter().enumerate() {
+            if !lenses.is_empty() {
+                let lenses = lenses
+                    .iter()
+                    .map(|(label, focal_len)| format!("[{label} {focal_len}]"))
+                    .join(" ");
+                writeln!(f, "Box {box_no}: {lenses}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The boxes after every step as in the walkthrough of the puzzle.
+fn trace(steps: &[Step]) -> String {
+    let mut boxes = Hashmap256::new();
+    let mut trace = String::new();
+    for step in steps {
+        boxes.apply(step);
+        trace += &format!("After \"{step}\":\n{boxes}\n");
+    }
+    trace
+}
+
+pub fn part_two(input: &str) -> Option<u32> {
+    let steps = match parse_steps(input) {
+        Ok(steps) => steps,
+        Err(e) => {
+            eprintln!("{e}");
+            return None;
+        }
+    };
+    let mut boxes = Hashmap256::new();
+    steps.iter().for_each(|step| boxes.apply(step));
+    Some(boxes.focusing_power())
+}
+
+/// `--trace` prints the boxes after every step.
+fn extras(input: &str) {
+    if std::env::args().any(|arg| arg == "--trace") {
+        if let Ok(steps) = parse_steps(input) {
+            print!("{}", trace(&steps));
+        }
+    }
 }
 
 fn hash(t: &str) -> u8 {
     t.chars().fold(0, hash_char)
 }
 
-#[allow(arithmetic_overflow)]
 fn hash_char(h: u8, c: char) -> u8 {
-    let h = h as u32;
-    let c = c as u32;
-    let i = h + c;
-    let m = i * 17;
-    (m % 256) as u8
+    ((h as u32 + c as u32) * 17 % 256) as u8
 }
 
 #[cfg(test)]
@@ -83,12 +175,15 @@ mod tests {
     #[test]
     fn test_hash() {
         assert_eq!(hash("HASH"), 52);
+        assert_eq!(hash("rn"), 0);
+        assert_eq!(hash("qp"), 1);
+        assert_eq!(hash("pc"), 3);
     }
 
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(1320));
     }
 
     #[test]
@@ -96,4 +191,67 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(145));
     }
+
+    #[test]
+    fn test_steps() {
+        assert_eq!(
+            parse_step("cm-"),
+            Ok(Step {
+                label: "cm".to_string(),
+                operation: Operation::Remove
+            })
+        );
+        assert_eq!(parse_step("ot=7").unwrap().to_string(), "ot=7");
+        assert!(parse_step("ot=x").is_err());
+        assert!(parse_step("ot").is_err());
+        assert!(parse_step("=1").is_err());
+    }
+
+    #[test]
+    fn test_hashmap() {
+        let mut boxes = Hashmap256::new();
+        boxes.insert("pc", 4);
+        boxes.insert("ab", 5);
+        boxes.insert("ot", 9);
+        // replaced in place, pc stays in front of ab
+        boxes.insert("pc", 6);
+        assert_eq!(boxes.to_string(), "Box 3: [pc 6] [ab 5] [ot 9]\n");
+        assert_eq!(boxes.remove("pc"), Some(6));
+        assert_eq!(boxes.remove("pc"), None);
+        assert_eq!(boxes.to_string(), "Box 3: [ab 5] [ot 9]\n");
+        assert_eq!(boxes.focusing_power(), 4 * 5 + 4 * 2 * 9);
+    }
+
+    #[test]
+    fn test_trace() {
+        let steps = parse_steps(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        let trace = trace(&steps);
+        // the walkthrough of the puzzle
+        assert!(trace.starts_with(
+            "After \"rn=1\":
+Box 0: [rn 1]
+
+After \"cm-\":
+Box 0: [rn 1]
+
+After \"qp=3\":
+Box 0: [rn 1]
+Box 1: [qp 3]
+
+After \"cm=2\":
+Box 0: [rn 1] [cm 2]
+Box 1: [qp 3]
+
+After \"qp-\":
+Box 0: [rn 1] [cm 2]
+"
+        ));
+        assert!(trace.ends_with(
+            "After \"ot=7\":
+Box 0: [rn 1] [cm 2]
+Box 3: [ot 7] [ab 5] [pc 6]
+
+"
+        ));
+    }
 }
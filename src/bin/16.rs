@@ -1,8 +1,10 @@
-use std::collections::HashSet;
+use std::cmp::Reverse;
 
+use advent_of_code::render::{self, Svg};
 use itertools::Itertools;
+use rayon::prelude::*;
 
-advent_of_code::solution!(16);
+advent_of_code::solution!(16, extras = extras);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum Direction {
@@ -12,6 +14,7 @@ enum Direction {
     Right,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct Beam {
     x: i32,
     y: i32,
@@ -63,15 +66,117 @@ impl Map {
     }
 }
 
+/// Which beams passed each tile, as a bit per [`Direction`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Energization {
+    width: i32,
+    height: i32,
+    directions: Vec<u8>,
+}
+
+impl Energization {
+    fn new(m: &Map) -> Self {
+        Self {
+            width: m.width,
+            height: m.height,
+            directions: vec![0; m.data.len()],
+        }
+    }
+
+    /// Records a beam on its tile, returns false if the same beam passed there before.
+    fn pass(&mut self, beam: &Beam) -> bool {
+        let bit = 1 << beam.direction as u8;
+        let tile = &mut self.directions[(beam.y * self.width + beam.x) as usize];
+        let new = *tile & bit == 0;
+        *tile |= bit;
+        new
+    }
+
+    /// The number of beams in different directions that passed the tile.
+    fn beams(&self, x: i32, y: i32) -> u32 {
+        self.directions[(y * self.width + x) as usize].count_ones()
+    }
+
+    fn energized(&self) -> u32 {
+        self.directions.iter().filter(|d| **d != 0).count() as u32
+    }
+
+    fn render(&self, tile: impl Fn(u32) -> char) -> String {
+        (0..self.height)
+            .map(|y| {
+                (0..self.width)
+                    .map(|x| tile(self.beams(x, y)))
+                    .collect::<String>()
+                    + "\n"
+            })
+            .collect()
+    }
+
+    /// Energized tiles as `#`, like the illustration of the puzzle.
+    fn to_ascii(&self) -> String {
+        self.render(|beams| if beams > 0 { '#' } else { '.' })
+    }
+
+    /// The number of beams passing each tile.
+    fn to_heatmap(&self) -> String {
+        self.render(|beams| match beams {
+            0 => '.',
+            n => char::from_digit(n, 10).unwrap_or('+'),
+        })
+    }
+
+    /// The contraption colored by the number of beams passing each tile.
+    fn to_svg(&self, m: &Map) -> Svg {
+        const CELL: usize = 16;
+        let mut svg = Svg::new(self.width as usize * CELL, self.height as usize * CELL);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (px, py) = (x as usize * CELL, y as usize * CELL);
+                let color = match self.beams(x, y) {
+                    0 => "#202020".to_string(),
+                    beams => render::heat_color(beams, 4),
+                };
+                svg.rect(px, py, CELL, CELL, &color);
+                let tile = m.get(x, y) as char;
+                if tile != '.' {
+                    let center = (px + CELL / 2, py + CELL / 2);
+                    svg.text(center.0, center.1, CELL, "white", &tile.to_string());
+                }
+            }
+        }
+        svg
+    }
+}
+
+/// `--energized` prints the energized tiles, `--beams` how many beams pass each tile and
+/// `--svg <file>` draws them into `file` with the part number added, e.g. `beams-1.svg`.
+fn show_energization(m: &Map, energization: &Energization, part: u8) {
+    let mut args = pico_args::Arguments::from_env();
+    if args.contains("--energized") {
+        println!("Part {part}, {} tiles energized:", energization.energized());
+        print!("{}", energization.to_ascii());
+    }
+    if args.contains("--beams") {
+        println!("Part {part}, beams passing each tile:");
+        print!("{}", energization.to_heatmap());
+    }
+    if let Ok(Some(file)) = args.opt_value_from_str::<_, String>("--svg") {
+        match energization.to_svg(m).write_for_part(&file, part) {
+            Ok(file) => println!("Wrote energization to {file}"),
+            Err(e) => eprintln!("Failed to write energization: {e}"),
+        }
+    }
+}
+
 pub fn part_one(input: &str) -> Option<u32> {
     let m = Map::new(input);
-    Some(simulate(Beam::new(-1, 0, Direction::Right), &m))
+    let energization = simulate(Beam::new(-1, 0, Direction::Right), &m);
+    Some(energization.energized())
 }
 
-fn simulate(beam: Beam, m: &Map) -> u32 {
+fn simulate(beam: Beam, m: &Map) -> Energization {
     let mut beams: Vec<Beam> = vec![beam];
-    let mut energized = HashSet::new();
-    let mut seen = HashSet::new();
+    let mut energization = Energization::new(m);
 
     while !beams.is_empty() {
         let mut next_beams = Vec::with_capacity(beams.len() * 2);
@@ -107,21 +212,15 @@ fn simulate(beam: Beam, m: &Map) -> u32 {
             next_beams.push(beam);
         });
 
-        next_beams.retain(|b| m.is_inside(b.x, b.y) && !seen.contains(&(b.x, b.y, b.direction)));
-
-        next_beams.iter().for_each(|p| {
-            energized.insert((p.x, p.y));
-            seen.insert((p.x, p.y, p.direction));
-        });
+        next_beams.retain(|b| m.is_inside(b.x, b.y) && energization.pass(b));
         beams = next_beams;
     }
-    // m.print(&energized);
-    energized.len() as u32
+    energization
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let m = Map::new(input);
-
+/// The entry beam that energizes the most tiles, the first one along the edges if there is a
+/// tie.
+fn best_entry(m: &Map) -> Option<(Beam, Energization)> {
     let left_to_right = (0..m.height).map(|y| Beam::new(-1, y, Direction::Right));
     let rigth_to_left = (0..m.height).map(|y| Beam::new(m.width, y, Direction::Left));
     let top_down = (0..m.width).map(|x| Beam::new(x, -1, Direction::Down));
@@ -132,13 +231,35 @@ pub fn part_two(input: &str) -> Option<u32> {
         .chain(top_down)
         .chain(bottom_up)
         .collect_vec();
-    dbg!(all.len());
-    let max = all
-        .into_iter()
-        .map(|it| simulate(it, &m))
-        .max()
-        .unwrap_or(0);
-    Some(max)
+    all.into_par_iter()
+        .enumerate()
+        .map(|(i, beam)| (i, beam, simulate(beam, m)))
+        .max_by_key(|(i, _, energization)| (energization.energized(), Reverse(*i)))
+        .map(|(_, beam, energization)| (beam, energization))
+}
+
+pub fn part_two(input: &str) -> Option<u32> {
+    let m = Map::new(input);
+    let (_, energization) = best_entry(&m)?;
+    Some(energization.energized())
+}
+
+/// Shows the energization of both parts, see [`show_energization`]. The best entry beam of
+/// part 2 is reported along with any of them, or on its own with `--best-entry`.
+fn extras(input: &str) {
+    let flags = ["--energized", "--beams", "--svg", "--best-entry"];
+    if !std::env::args().any(|arg| flags.contains(&arg.as_str())) {
+        return;
+    }
+    let m = Map::new(input);
+    show_energization(&m, &simulate(Beam::new(-1, 0, Direction::Right), &m), 1);
+    if let Some((beam, energization)) = best_entry(&m) {
+        println!(
+            "Best entry beam: going {:?} from ({}, {})",
+            beam.direction, beam.x, beam.y
+        );
+        show_energization(&m, &energization, 2);
+    }
 }
 
 #[cfg(test)]
@@ -154,6 +275,45 @@ mod tests {
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(51));
+    }
+
+    #[test]
+    fn test_energization() {
+        let m = Map::new(&advent_of_code::template::read_file("examples", DAY));
+        let energization = simulate(Beam::new(-1, 0, Direction::Right), &m);
+        // the illustration of the puzzle
+        assert_eq!(
+            energization.to_ascii(),
+            "######....
+.#...#....
+.#...#####
+.#...##...
+.#...##...
+.#...##...
+.#..####..
+########..
+.#######..
+.#...#.#..
+"
+        );
+        assert_eq!(energization.beams(0, 0), 1);
+        // the beam to the right and the one coming back up from the splitter below
+        assert_eq!(energization.beams(5, 6), 2);
+        let heatmap = energization.to_heatmap();
+        // the splitter in the top left corner is also hit by the beam coming up from the bottom
+        assert_eq!(heatmap.lines().next(), Some("121111...."));
+
+        let svg = energization.to_svg(&m).to_string();
+        assert_eq!(svg.matches("<rect").count(), 100);
+        assert_eq!(svg.matches("<text").count(), 23);
+    }
+
+    #[test]
+    fn test_best_entry() {
+        let m = Map::new(&advent_of_code::template::read_file("examples", DAY));
+        let (beam, energization) = best_entry(&m).unwrap();
+        assert_eq!(beam, Beam::new(3, -1, Direction::Down));
+        assert_eq!(energization.energized(), 51);
     }
 }
@@ -1,6 +1,11 @@
 use core::panic;
 use std::collections::{BinaryHeap, HashMap};
-advent_of_code::solution!(17);
+
+use advent_of_code::{
+    parse,
+    render::{self, Svg},
+};
+advent_of_code::solution!(17, extras = extras);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 struct Point {
@@ -76,80 +81,323 @@ impl Map {
     }
 }
 
-static START_CAME_FROM_DIR: Point = Point { x: 0, y: 0 };
+/// The rules a crucible moves by.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Crucible {
+    start: Point,
+    end: Point,
+    /// The directions the first run may go in.
+    start_directions: Vec<Point>,
+    min_steps: i32,
+    max_steps: i32,
+    /// What entering a block in each direction costs, as a multiple of its heat loss, in the
+    /// order of [`DIRECTIONS`].
+    cost_factors: [i32; 4],
+    /// Whether a run may go back the way the previous one came.
+    can_reverse: bool,
+}
+
+impl Crucible {
+    /// A crucible going from the top left to the bottom right corner of `map`.
+    fn new(map: &Map, min_steps: i32, max_steps: i32) -> Self {
+        Crucible {
+            start: Point::new(0, 0),
+            end: Point::new(map.width - 1, map.height - 1),
+            start_directions: DIRECTIONS.to_vec(),
+            min_steps,
+            max_steps,
+            cost_factors: [1; 4],
+            can_reverse: false,
+        }
+    }
+
+    /// The directions a run may go in after a run in direction `last`, by index into
+    /// [`DIRECTIONS`].
+    fn next_directions(&self, last: Option<usize>) -> Vec<usize> {
+        (0..DIRECTIONS.len())
+            .filter(|&d| match last {
+                None => self.start_directions.contains(&DIRECTIONS[d]),
+                // runs into the same direction have all been tried already
+                Some(last) => {
+                    d != last
+                        && (self.can_reverse || !DIRECTIONS[d].is_opposite_of(&DIRECTIONS[last]))
+                }
+            })
+            .collect()
+    }
+}
 
 // (cost, destination, direction we came from)
-type QueueItem = (i32, (Point, &'static Point));
+type QueueItem = (i32, State);
+
+/// A position and the index of the direction of the straight run that led there, [`None`] at
+/// the start.
+type State = (Point, Option<usize>);
+
+/// The best path through the map as straight runs, each a direction and the number of steps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Route {
+    cost: i32,
+    start: Point,
+    legs: Vec<(Point, i32)>,
+}
+
+impl Route {
+    /// Every tile entered on the way, with the direction it was entered in.
+    fn tiles(&self) -> Vec<(Point, Point)> {
+        let mut pos = self.start;
+        let mut tiles = vec![];
+        for (d, steps) in &self.legs {
+            for _ in 0..*steps {
+                pos = pos.add(d);
+                tiles.push((pos, *d));
+            }
+        }
+        tiles
+    }
+
+    /// The runs as arrows and lengths, like `>2 v1 >3`.
+    fn describe(&self) -> String {
+        self.legs
+            .iter()
+            .map(|(d, steps)| format!("{}{steps}", arrow(d)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+fn arrow(direction: &Point) -> char {
+    match (direction.x, direction.y) {
+        (0, -1) => '^',
+        (0, 1) => 'v',
+        (-1, 0) => '<',
+        (1, 0) => '>',
+        _ => '?',
+    }
+}
 
-fn a_star(start: Point, end: Point, map: &Map, min_steps: i32, max_steps: i32) -> i32 {
+fn a_star(map: &Map, crucible: &Crucible) -> Option<Route> {
+    let (start, end) = (crucible.start, crucible.end);
+    if !map.is_inside(&start) || !map.is_inside(&end) {
+        return None;
+    }
     let mut queue: BinaryHeap<QueueItem> = BinaryHeap::new();
 
     let mut costs = HashMap::new();
-    // special came from for the start
-    queue.push((0i32, (start, &START_CAME_FROM_DIR)));
-    costs.insert((start, &START_CAME_FROM_DIR), 0);
-    let mut best_cost = i32::MAX;
+    // the state each state was reached from, to walk the best path back
+    let mut came_from: HashMap<State, State> = HashMap::new();
+    queue.push((0i32, (start, None)));
+    costs.insert((start, None), 0);
+    let mut best = None;
     while let Some((cost, (pos, coming_from))) = queue.pop() {
         let cost = cost.abs();
-        if pos == end && cost < best_cost {
-            best_cost = cost;
-            println!("Found path with cost {}", cost);
+        if pos == end {
+            best = Some((cost, (pos, coming_from)));
             break;
-        } else if cost > best_cost {
-            continue;
         }
 
-        for d in &DIRECTIONS {
-            // if we came from the same direction, we already tried the 1,2,3 steps, so skip
-            // and we cannot reverse
-            if d.eq(coming_from) || d.is_opposite_of(coming_from) {
-                continue;
-            }
+        for d in crucible.next_directions(coming_from) {
             let mut next_costs = cost;
             // eagerly try all possible steps into the same direction and keep the direction in mind,
             // so we dont retry the same direction on this field later
-            for steps in 1..=max_steps {
-                let next_d = d.scale(steps);
+            for steps in 1..=crucible.max_steps {
+                let next_d = DIRECTIONS[d].scale(steps);
                 let next_pos = pos.add(&next_d);
                 if map.is_inside(&next_pos) {
-                    let known_cost = costs.get(&(next_pos, d)).unwrap_or(&i32::MAX).abs();
-                    next_costs += map.get(&next_pos) as i32;
+                    let known_cost = costs.get(&(next_pos, Some(d))).unwrap_or(&i32::MAX).abs();
+                    next_costs += map.get(&next_pos) as i32 * crucible.cost_factors[d];
 
                     // skip illegal steps (bart b), skip if we already know a better solution
-                    if steps >= min_steps && next_costs < best_cost && known_cost > next_costs {
-                        costs.insert((next_pos, d), next_costs);
-                        queue.push((-next_costs, (next_pos, d)));
+                    if steps >= crucible.min_steps && known_cost > next_costs {
+                        costs.insert((next_pos, Some(d)), next_costs);
+                        came_from.insert((next_pos, Some(d)), (pos, coming_from));
+                        queue.push((-next_costs, (next_pos, Some(d))));
                     }
                 }
             }
         }
     }
-    best_cost
+
+    let (cost, mut state) = best?;
+    let mut legs = vec![];
+    while let Some(&previous) = came_from.get(&state) {
+        let (pos, d) = state;
+        let steps = (pos.x - previous.0.x).abs() + (pos.y - previous.0.y).abs();
+        legs.push((DIRECTIONS[d?], steps));
+        state = previous;
+    }
+    legs.reverse();
+    Some(Route { cost, start, legs })
+}
+
+fn parse_point(value: &str) -> Result<Point, String> {
+    let (x, y) = value
+        .split_once(',')
+        .ok_or_else(|| format!("expected x,y, found {value}"))?;
+    let coordinate = |c: &str| {
+        c.trim()
+            .parse::<i32>()
+            .map_err(|e| format!("invalid coordinate {c}: {e}"))
+    };
+    Ok(Point::new(coordinate(x)?, coordinate(y)?))
+}
+
+/// A direction as compass letter or arrow, e.g. `E` or `>`.
+fn parse_direction(value: &str) -> Result<usize, String> {
+    match value.trim() {
+        "N" | "^" => Ok(0),
+        "S" | "v" => Ok(1),
+        "W" | "<" => Ok(2),
+        "E" | ">" => Ok(3),
+        _ => Err(format!("unknown direction {value}, expecting N, S, W or E")),
+    }
+}
+
+/// Changes the rules of `crucible` by the options in `args`:
+///
+/// - `--start <x,y>` and `--end <x,y>` move the start and the end
+/// - `--start-directions <N,S,W,E>` limits the directions of the first run
+/// - `--steps <min..max>` changes how far every run goes
+/// - `--cost-factors <N=2,E=1,..>` multiplies the heat loss when moving in a direction
+/// - `--can-reverse` allows runs back the way the previous one came
+fn apply_options(
+    mut crucible: Crucible,
+    args: &mut pico_args::Arguments,
+) -> Result<Crucible, String> {
+    let mut option = |name| {
+        args.opt_value_from_str::<_, String>(name)
+            .map_err(|e| e.to_string())
+    };
+    if let Some(start) = option("--start")? {
+        crucible.start = parse_point(&start)?;
+    }
+    if let Some(end) = option("--end")? {
+        crucible.end = parse_point(&end)?;
+    }
+    if let Some(directions) = option("--start-directions")? {
+        crucible.start_directions = directions
+            .split(',')
+            .map(|d| parse_direction(d).map(|d| DIRECTIONS[d]))
+            .collect::<Result<_, _>>()?;
+    }
+    if let Some(steps) = option("--steps")? {
+        let (min, max) = steps
+            .split_once("..")
+            .and_then(|(min, max)| Some((min.parse().ok()?, max.parse().ok()?)))
+            .ok_or_else(|| format!("expected min..max, found {steps}"))?;
+        (crucible.min_steps, crucible.max_steps) = (min, max);
+    }
+    if let Some(factors) = option("--cost-factors")? {
+        for (direction, factor) in
+            parse::key_values(&factors, ",", "=").map_err(|e| e.to_string())?
+        {
+            let factor = factor
+                .parse::<i32>()
+                .ok()
+                .filter(|f| *f >= 0)
+                .ok_or_else(|| format!("invalid cost factor {factor}"))?;
+            crucible.cost_factors[parse_direction(direction)?] = factor;
+        }
+    }
+    crucible.can_reverse |= args.contains("--can-reverse");
+    Ok(crucible)
+}
+
+/// The crucible moving by the rules of the puzzle, changed by the command line options.
+fn crucible_from_env(map: &Map, min_steps: i32, max_steps: i32) -> Option<Crucible> {
+    let crucible = Crucible::new(map, min_steps, max_steps);
+    match apply_options(crucible, &mut pico_args::Arguments::from_env()) {
+        Ok(crucible) => Some(crucible),
+        Err(e) => {
+            eprintln!("Invalid crucible options: {e}");
+            None
+        }
+    }
+}
+
+/// The map with the route drawn in as arrows, like the illustration of the puzzle.
+fn render_ascii(map: &Map, route: &Route) -> String {
+    let mut rows = map
+        .data
+        .iter()
+        .map(|row| row.iter().map(|h| (b'0' + h) as char).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    for (pos, d) in route.tiles() {
+        rows[pos.y as usize][pos.x as usize] = arrow(&d);
+    }
+    rows.iter()
+        .map(|row| row.iter().collect::<String>() + "\n")
+        .collect()
+}
+
+/// The map colored by heat loss with the route drawn over it.
+fn render_svg(map: &Map, route: &Route) -> Svg {
+    const CELL: usize = 16;
+    let center = |p: Point| {
+        (
+            p.x as usize * CELL + CELL / 2,
+            p.y as usize * CELL + CELL / 2,
+        )
+    };
+    let mut svg = Svg::new(map.width as usize * CELL, map.height as usize * CELL);
+    for (y, row) in map.data.iter().enumerate() {
+        for (x, heat_loss) in row.iter().enumerate() {
+            let color = render::heat_color(*heat_loss as u32, 9);
+            svg.rect(x * CELL, y * CELL, CELL, CELL, &color);
+            let (cx, cy) = center(Point::new(x as i32, y as i32));
+            svg.text(cx, cy, CELL * 3 / 4, "white", &heat_loss.to_string());
+        }
+    }
+    let points = std::iter::once(route.start)
+        .chain(route.tiles().into_iter().map(|(p, _)| p))
+        .map(center)
+        .collect::<Vec<_>>();
+    svg.polyline(&points, "black", CELL / 4);
+    for point in [points.first(), points.last()].into_iter().flatten() {
+        svg.circle(point.0, point.1, CELL / 4, "black");
+    }
+    svg
+}
+
+/// `--route` prints the best route over the map, `--svg <file>` draws it into `file` with the
+/// part number added to the name, e.g. `route-1.svg`.
+fn show_route(map: &Map, route: &Route, part: u8) {
+    let mut args = pico_args::Arguments::from_env();
+    if args.contains("--route") {
+        println!("Part {part}, cost {}: {}", route.cost, route.describe());
+        print!("{}", render_ascii(map, route));
+    }
+    if let Ok(Some(file)) = args.opt_value_from_str::<_, String>("--svg") {
+        match render_svg(map, route).write_for_part(&file, part) {
+            Ok(file) => println!("Wrote route to {file}"),
+            Err(e) => eprintln!("Failed to write route: {e}"),
+        }
+    }
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
     let m = Map::new(input);
-
-    let best = a_star(
-        Point::new(0, 0),
-        Point::new(m.width - 1, m.height - 1),
-        &m,
-        1,
-        3,
-    );
-    Some(best as u32)
+    let route = a_star(&m, &crucible_from_env(&m, 1, 3)?)?;
+    Some(route.cost as u32)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
     let m = Map::new(input);
-    let best = a_star(
-        Point::new(0, 0),
-        Point::new(m.width - 1, m.height - 1),
-        &m,
-        4,
-        10,
-    );
-    Some(best as u32)
+    let route = a_star(&m, &crucible_from_env(&m, 4, 10)?)?;
+    Some(route.cost as u32)
+}
+
+/// Shows the routes of both parts, see [`show_route`].
+fn extras(input: &str) {
+    if !std::env::args().any(|arg| arg == "--route" || arg == "--svg") {
+        return;
+    }
+    let m = Map::new(input);
+    for (part, min, max) in [(1, 1, 3), (2, 4, 10)] {
+        if let Some(route) = crucible_from_env(&m, min, max).and_then(|c| a_star(&m, &c)) {
+            show_route(&m, &route, part);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -162,6 +410,135 @@ mod tests {
         assert_eq!(result, Some(102));
     }
 
+    #[test]
+    fn test_route() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let m = Map::new(&input);
+        let end = Point::new(m.width - 1, m.height - 1);
+        for (min_steps, max_steps, cost) in [(1, 3, 102), (4, 10, 94)] {
+            let route = a_star(&m, &Crucible::new(&m, min_steps, max_steps)).unwrap();
+            assert_eq!(route.cost, cost);
+            let tiles = route.tiles();
+            assert_eq!(tiles.last().unwrap().0, end);
+            let heat_loss: i32 = tiles.iter().map(|(p, _)| m.get(p) as i32).sum();
+            assert_eq!(heat_loss, cost);
+            assert!(route
+                .legs
+                .iter()
+                .all(|(_, steps)| (min_steps..=max_steps).contains(steps)));
+        }
+
+        // the route illustrated in the puzzle
+        let route = a_star(&m, &Crucible::new(&m, 1, 3)).unwrap();
+        assert_eq!(
+            render_ascii(&m, &route),
+            "2>>34^>>>1323
+32v>>>35v5623
+32552456v>>54
+3446585845v52
+4546657867v>6
+14385987984v4
+44578769877v6
+36378779796v>
+465496798688v
+456467998645v
+12246868655<v
+25465488877v5
+43226746555v>
+"
+        );
+    }
+
+    #[test]
+    fn test_crucible_rules() {
+        let m = Map::new("1111\n9991\n1111");
+        let standard = Crucible::new(&m, 1, 3);
+        // along the top and down the right side
+        assert_eq!(a_star(&m, &standard).unwrap().describe(), ">3 v2");
+
+        // every way needs three moves to the east, but they cost ten times as much now
+        let mut crucible = standard.clone();
+        crucible.cost_factors = [1, 1, 1, 10];
+        assert_eq!(a_star(&m, &crucible).unwrap().cost, 10 * 3 + 2);
+
+        // from the bottom left corner, but only starting upwards
+        let mut crucible = standard.clone();
+        crucible.start = Point::new(0, 2);
+        crucible.start_directions = vec![Point::new(0, -1)];
+        assert_eq!(a_star(&m, &crucible).unwrap().describe(), "^2 >3 v2");
+
+        // reversing onto the 9s is allowed, but never worth it here
+        let mut crucible = standard.clone();
+        crucible.can_reverse = true;
+        assert_eq!(a_star(&m, &crucible).unwrap().cost, 5);
+        // but turning around is the only way in a single row
+        let m = Map::new("1111");
+        let mut crucible = Crucible::new(&m, 2, 3);
+        crucible.end = Point::new(1, 0);
+        assert_eq!(a_star(&m, &crucible), None);
+        crucible.can_reverse = true;
+        assert_eq!(a_star(&m, &crucible).unwrap().describe(), ">3 <2");
+
+        // outside of the map
+        crucible.end = Point::new(4, 0);
+        assert_eq!(a_star(&m, &crucible), None);
+    }
+
+    #[test]
+    fn test_options() {
+        let m = Map::new("1111\n9991\n1111");
+        let args =
+            |args: &[&str]| pico_args::Arguments::from_vec(args.iter().map(|a| a.into()).collect());
+        let crucible = apply_options(
+            Crucible::new(&m, 1, 3),
+            &mut args(&[
+                "--start",
+                "0,2",
+                "--end",
+                "3,0",
+                "--start-directions",
+                "N,>",
+                "--steps",
+                "2..5",
+                "--cost-factors",
+                "E=3,W=0",
+                "--can-reverse",
+            ]),
+        )
+        .unwrap();
+        assert_eq!(
+            crucible,
+            Crucible {
+                start: Point::new(0, 2),
+                end: Point::new(3, 0),
+                start_directions: vec![Point::new(0, -1), Point::new(1, 0)],
+                min_steps: 2,
+                max_steps: 5,
+                cost_factors: [1, 1, 0, 3],
+                can_reverse: true,
+            }
+        );
+        for invalid in [
+            ["--start", "0"],
+            ["--start-directions", "X"],
+            ["--steps", "3"],
+            ["--cost-factors", "N=-1"],
+        ] {
+            assert!(apply_options(Crucible::new(&m, 1, 3), &mut args(&invalid)).is_err());
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let m = Map::new("19\n11");
+        let route = a_star(&m, &Crucible::new(&m, 1, 3)).unwrap();
+        assert_eq!(route.cost, 2);
+        assert_eq!(route.describe(), "v1 >1");
+        assert_eq!(render_ascii(&m, &route), "19\nv>\n");
+        let svg = render_svg(&m, &route).to_string();
+        assert!(svg.contains(r#"<polyline points="8,8 8,24 24,24""#));
+    }
+
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
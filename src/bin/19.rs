@@ -1,15 +1,56 @@
-use std::{collections::HashMap, str::FromStr, string::ParseError};
+use std::{collections::HashMap, fmt::Display, str::FromStr};
 
-use regex::Regex;
+use advent_of_code::{
+    graph::Digraph,
+    parse::{self, ParseError, Section},
+    pattern,
+};
+use itertools::Itertools;
 
-advent_of_code::solution!(19);
+advent_of_code::solution!(19, extras = extras);
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum Operator {
+    Less,
+    Greater,
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operator::Less => f.write_str("<"),
+            Operator::Greater => f.write_str(">"),
+        }
+    }
+}
+
+impl FromStr for Operator {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "<" => Ok(Operator::Less),
+            ">" => Ok(Operator::Greater),
+            _ => Err(ParseError::at(s, s, format!("unknown operator {s}"))),
+        }
+    }
+}
 
 #[derive(PartialEq, Debug)]
 enum Rule {
-    Condition(String, String, i32, String),
+    Condition(String, Operator, i32, String),
     Jump(String),
 }
 
+impl Rule {
+    /// The workflow the rule sends parts to, or `A` / `R`.
+    fn target(&self) -> &str {
+        match self {
+            Rule::Condition(_, _, _, target) | Rule::Jump(target) => target,
+        }
+    }
+}
+
 #[derive(PartialEq, Debug)]
 struct Instruction {
     name: String,
@@ -18,54 +59,160 @@ struct Instruction {
 
 #[derive(PartialEq, Debug)]
 struct Part {
-    variables: HashMap<String, i32>,
+    /// The ratings, in the order of [`Categories::names`]. None for categories the part is not
+    /// rated in, they add nothing to its score and no condition on them matches.
+    values: Vec<Option<i32>>,
 }
 
 impl Part {
     fn score(&self) -> i32 {
-        self.variables.get("x").unwrap()
-            + self.variables.get("m").unwrap()
-            + self.variables.get("a").unwrap()
-            + self.variables.get("s").unwrap()
+        self.values.iter().flatten().sum()
     }
 }
 
-impl FromStr for Part {
-    type Err = ParseError;
+/// The categories parts are rated in, and the range of ratings possible in each of them.
+/// Conditions and solution spaces refer to categories by their index.
+#[derive(PartialEq, Eq, Debug, Clone)]
+struct Categories {
+    names: Vec<String>,
+    bounds: Vec<Range>,
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let regex = Regex::new(r"x=(\d+),m=(\d+),a=(\d+),s=(\d+)").unwrap();
-        let captures = regex.captures(s).unwrap();
-        let x = captures.get(1).unwrap().as_str().parse::<i32>().unwrap();
-        let m = captures.get(2).unwrap().as_str().parse::<i32>().unwrap();
-        let a = captures.get(3).unwrap().as_str().parse::<i32>().unwrap();
-        let s = captures.get(4).unwrap().as_str().parse::<i32>().unwrap();
-        Ok(Part {
-            variables: HashMap::from_iter([
-                ("x".to_string(), x),
-                ("m".to_string(), m),
-                ("a".to_string(), a),
-                ("s".to_string(), s),
-            ]),
-        })
+impl Categories {
+    const DEFAULT_BOUNDS: Range = (1, 4000);
+
+    fn new<S: Into<String>>(names: impl IntoIterator<Item = S>) -> Self {
+        let names = names.into_iter().map(Into::into).collect_vec();
+        let bounds = vec![Self::DEFAULT_BOUNDS; names.len()];
+        Categories { names, bounds }
+    }
+
+    /// The categories the parts are rated in, in order of their first appearance, followed by
+    /// the ones that only conditions refer to, sorted by name.
+    fn infer(
+        instructions: &HashMap<String, Instruction>,
+        parts: &Section,
+    ) -> Result<Self, ParseError> {
+        let mut names = vec![];
+        for line in parts.lines() {
+            for (name, _) in parse::key_values(line.trim_matches(['{', '}']), ",", "=")? {
+                if !names.iter().any(|n| n == name) {
+                    names.push(name.to_string());
+                }
+            }
+        }
+        let conditions = instructions
+            .values()
+            .flat_map(|i| &i.rules)
+            .filter_map(|rule| match rule {
+                Rule::Condition(category, ..) => Some(category),
+                Rule::Jump(_) => None,
+            })
+            .filter(|category| !names.contains(category))
+            .sorted()
+            .dedup()
+            .cloned()
+            .collect_vec();
+        names.extend(conditions);
+        Ok(Self::new(names))
+    }
+
+    fn index(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
     }
+
+    /// Reads a part like `{x=787,m=2655,a=1222,s=2876}`, every category is rated at most once.
+    fn parse_part(&self, line: &str) -> Result<Part, ParseError> {
+        let mut values = vec![None; self.names.len()];
+        for (name, value) in parse::key_values(line.trim_matches(['{', '}']), ",", "=")? {
+            let index = self
+                .index(name)
+                .ok_or_else(|| ParseError::at(line, name, format!("unknown category {name}")))?;
+            if values[index].replace(parse::value(line, value)?).is_some() {
+                return Err(ParseError::at(line, name, format!("{name} is rated twice")));
+            }
+        }
+        Ok(Part { values })
+    }
+
+    fn format_part(&self, part: &Part) -> String {
+        let ratings = self
+            .names
+            .iter()
+            .zip(&part.values)
+            .filter_map(|(name, value)| value.map(|value| format!("{name}={value}")));
+        format!("{{{}}}", ratings.format(","))
+    }
+}
+
+/// Reads a range like `1..4000`.
+fn parse_range(text: &str, range: &str) -> Result<Range, ParseError> {
+    let (low, high) = parse::split_once(range, "..")?;
+    Ok((parse::value(text, low)?, parse::value(text, high)?))
+}
+
+/// Replaces the category names with the comma separated `names`, and sets their bounds from
+/// either a range for all of them like `0..9` or ranges by name like `x=1..10,m=5..20`.
+fn configure_categories(
+    mut categories: Categories,
+    names: Option<&str>,
+    bounds: Option<&str>,
+) -> Result<Categories, ParseError> {
+    if let Some(names) = names {
+        categories = Categories::new(names.split(',').map(str::trim));
+    }
+    match bounds {
+        Some(bounds) if bounds.contains('=') => {
+            for (name, range) in parse::key_values(bounds, ",", "=")? {
+                let index = categories.index(name).ok_or_else(|| {
+                    ParseError::at(bounds, name, format!("unknown category {name}"))
+                })?;
+                categories.bounds[index] = parse_range(bounds, range)?;
+            }
+        }
+        Some(bounds) => {
+            let range = parse_range(bounds, bounds)?;
+            categories.bounds.fill(range);
+        }
+        None => {}
+    }
+    Ok(categories)
+}
+
+/// `--categories <name>,..` rates parts in the given categories instead of the ones found in
+/// the input, `--bounds <low>..<high>` or `--bounds <name>=<low>..<high>,..` changes the
+/// ratings possible in each of them from `1..4000`.
+fn category_options(categories: Categories) -> Result<Categories, ParseError> {
+    let mut args = pico_args::Arguments::from_env();
+    let mut option = |name| args.opt_value_from_str::<_, String>(name).ok().flatten();
+    let names = option("--categories");
+    let bounds = option("--bounds");
+    configure_categories(categories, names.as_deref(), bounds.as_deref())
 }
 
 impl FromStr for Rule {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let regex = Regex::new(r"(\w+)(.)(\d+):(\w+)").unwrap();
-
-        if let Some(captures) = regex.captures(s) {
-            let variable = captures.get(1).unwrap().as_str().to_string();
-            let operator = captures.get(2).unwrap().as_str().to_string();
-            let value = captures.get(3).unwrap().as_str().parse::<i32>().unwrap();
-            let action = captures.get(4).unwrap().as_str().to_string();
-            Ok(Rule::Condition(variable, operator, value, action))
-        } else {
-            Ok(Rule::Jump(s.to_string()))
-        }
+        parse_rule(s, s)
+    }
+}
+
+/// Reads `rule`, a slice of `line`, errors are positioned within `line`.
+fn parse_rule(line: &str, rule: &str) -> Result<Rule, ParseError> {
+    if let Ok([variable, operator, value, action]) =
+        pattern!(r"(\w+)(.)(\d+):(\w+)").captures::<4>(rule)
+    {
+        Ok(Rule::Condition(
+            variable.to_string(),
+            operator.parse().map_err(|_| {
+                ParseError::at(line, operator, format!("unknown operator {operator}"))
+            })?,
+            parse::value(line, value)?,
+            action.to_string(),
+        ))
+    } else {
+        Ok(Rule::Jump(rule.to_string()))
     }
 }
 
@@ -73,175 +220,747 @@ impl FromStr for Instruction {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = s.trim_matches('}').split('{').collect();
-        let name = parts[0].to_string();
-        let rules_str = parts[1].split(',').collect::<Vec<&str>>();
-        let mut rules = Vec::new();
-        for rule_str in rules_str {
-            rules.push(rule_str.parse::<Rule>().unwrap());
+        let (name, rules_str) = parse::split_once(s.trim_end_matches('}'), "{")?;
+        let rules = rules_str
+            .split(',')
+            .map(|rule_str| parse_rule(s, rule_str))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Instruction {
+            name: name.to_string(),
+            rules,
+        })
+    }
+}
+
+fn parse_instructions(section: &Section) -> Result<HashMap<String, Instruction>, ParseError> {
+    let instructions = section.parse_lines(|line| line.parse::<Instruction>())?;
+    Ok(instructions
+        .into_iter()
+        .map(|i| (i.name.clone(), i))
+        .collect())
+}
+
+fn workflow_graph(instructions: &HashMap<String, Instruction>) -> Digraph {
+    Digraph::from_adjacencies(
+        instructions
+            .iter()
+            .map(|(name, i)| (name, i.rules.iter().map(Rule::target))),
+    )
+}
+
+/// Workflows that no part ever reaches when starting at `in`, sorted by name.
+fn unreachable_workflows(instructions: &HashMap<String, Instruction>) -> Vec<String> {
+    let graph = workflow_graph(instructions);
+    let reachable = graph
+        .id("in")
+        .map(|id| graph.descendants([id]))
+        .unwrap_or_default();
+    instructions
+        .keys()
+        .filter(|name| {
+            graph
+                .id(name)
+                .is_some_and(|id| reachable.binary_search(&id).is_err())
+        })
+        .cloned()
+        .sorted()
+        .collect()
+}
+
+/// Cycles of workflows reachable from `in`, parts caught in them would never be sorted.
+fn workflow_cycles(instructions: &HashMap<String, Instruction>) -> Vec<Vec<String>> {
+    let graph = workflow_graph(instructions);
+    let reachable = graph
+        .id("in")
+        .map(|id| graph.descendants([id]))
+        .unwrap_or_default();
+    graph
+        .cyclic_components()
+        .into_iter()
+        .filter(|c| reachable.binary_search(&c[0]).is_ok())
+        .map(|c| graph.names(&c).into_iter().map(String::from).collect())
+        .collect()
+}
+
+/// Parses the workflows and parts, checks that every part ends up accepted or rejected and
+/// compiles the workflows for the categories the parts are rated in.
+fn parse_input(input: &str) -> Option<(Program, Vec<Part>)> {
+    let [instructions, parts] = parse::sections_n(input).ok()?;
+    let instructions = parse_instructions(&instructions).ok()?;
+    if !workflow_cycles(&instructions).is_empty() {
+        return None;
+    }
+    let categories = Categories::infer(&instructions, &parts)
+        .and_then(category_options)
+        .ok()?;
+    let program = match Program::compile(&instructions, categories) {
+        Ok(program) => program,
+        Err(e) => {
+            eprintln!("Could not compile the workflows: {e:?}");
+            return None;
+        }
+    };
+    let parts = parts
+        .parse_lines(|line| program.categories.parse_part(line))
+        .ok()?;
+    Some((program, parts))
+}
+
+/// Where a rule sends a part.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum Target {
+    Accept,
+    Reject,
+    /// The workflow at this index of [`Program::workflows`].
+    Workflow(usize),
+}
+
+/// An inclusive range of ratings.
+type Range = (i32, i32);
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+struct Condition {
+    /// The index into [`Categories::names`].
+    category: usize,
+    operator: Operator,
+    value: i32,
+}
+
+impl Condition {
+    fn matches(&self, part: &Part) -> bool {
+        let Some(rating) = part.values[self.category] else {
+            return false;
+        };
+        match self.operator {
+            Operator::Less => rating < self.value,
+            Operator::Greater => rating > self.value,
         }
+    }
+
+    /// Splits the inclusive range `(low, high)` into the values that do and do not match.
+    fn split(&self, (low, high): Range) -> (Option<Range>, Option<Range>) {
+        // no rating is below `i32::MIN` or above `i32::MAX`, so those sides are empty
+        let (yes, no) = match self.operator {
+            Operator::Less => (
+                self.value
+                    .checked_sub(1)
+                    .map(|below| (low, high.min(below))),
+                (low.max(self.value), high),
+            ),
+            Operator::Greater => (
+                self.value
+                    .checked_add(1)
+                    .map(|above| (low.max(above), high)),
+                (low, high.min(self.value)),
+            ),
+        };
+        let non_empty = |(l, h): Range| (l <= h).then_some((l, h));
+        (yes.and_then(non_empty), non_empty(no))
+    }
+}
 
-        Ok(Instruction { name, rules })
-    }
-}
-
-fn process(part: &Part, instructions: &HashMap<String, Instruction>) -> bool {
-    let mut inst = instructions.get("in");
-    while let Some(i) = inst {
-        for rule in &i.rules {
-            match rule {
-                Rule::Condition(variable, operator, value, action) => {
-                    let variable_value = part.variables.get(variable).unwrap();
-                    let target = match operator.as_str() {
-                        ">" => {
-                            if variable_value > value {
-                                Some(action)
-                            } else {
-                                None
-                            }
-                        }
-                        "<" => {
-                            if variable_value < value {
-                                Some(action)
-                            } else {
-                                None
-                            }
-                        }
-                        _ => panic!("Unknown operator {}", operator),
-                    };
-
-                    if let Some(action) = target {
-                        match action.as_str() {
-                            "A" => return true,
-                            "R" => return false,
-                            _ => inst = instructions.get(action),
-                        }
-                        break;
+/// A compiled workflow, its rules as a chain of branches ending in the fallback.
+#[derive(PartialEq, Eq, Debug, Clone)]
+enum Decision {
+    Go(Target),
+    Branch {
+        condition: Condition,
+        then: Target,
+        otherwise: Box<Decision>,
+    },
+}
+
+impl Decision {
+    /// Where the part goes and the index of the rule that sent it there.
+    fn decide(&self, part: &Part) -> (usize, Target) {
+        let mut decision = self;
+        let mut rule = 0;
+        loop {
+            match decision {
+                Decision::Go(target) => return (rule, *target),
+                Decision::Branch {
+                    condition,
+                    then,
+                    otherwise,
+                } => {
+                    if condition.matches(part) {
+                        return (rule, *then);
                     }
+                    decision = otherwise;
+                    rule += 1;
+                }
+            }
+        }
+    }
+
+    /// The chain starting at the rule with the given index.
+    fn rule(&self, index: usize) -> Option<&Decision> {
+        let mut decision = self;
+        for _ in 0..index {
+            match decision {
+                Decision::Go(_) => return None,
+                Decision::Branch { otherwise, .. } => decision = otherwise,
+            }
+        }
+        Some(decision)
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+struct Workflow {
+    name: String,
+    decision: Decision,
+}
+
+/// Why workflows could not be compiled.
+#[derive(Debug, PartialEq, Eq)]
+enum CompileError {
+    /// There is no `in` workflow to start with.
+    MissingStart,
+    /// A rule sends parts to a workflow that does not exist.
+    UnknownWorkflow(String),
+    /// A condition compares a category that parts are not rated in.
+    UnknownCategory(String),
+    /// The workflow does not end with a rule without condition, or has one before its end.
+    MisplacedFallback(String),
+}
+
+/// The workflows with resolved names and categories.
+#[derive(PartialEq, Eq, Debug, Clone)]
+struct Program {
+    /// Sorted by name.
+    workflows: Vec<Workflow>,
+    start: usize,
+    categories: Categories,
+    /// Workflows no rule leads to when starting at `in`, whatever the ratings.
+    unused: Vec<String>,
+}
+
+impl Program {
+    fn compile(
+        instructions: &HashMap<String, Instruction>,
+        categories: Categories,
+    ) -> Result<Self, CompileError> {
+        let names = instructions.keys().sorted().collect_vec();
+        let index: HashMap<&str, usize> = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.as_str(), i))
+            .collect();
+        let target = |name: &str| match name {
+            "A" => Ok(Target::Accept),
+            "R" => Ok(Target::Reject),
+            _ => index
+                .get(name)
+                .map(|&i| Target::Workflow(i))
+                .ok_or_else(|| CompileError::UnknownWorkflow(name.to_string())),
+        };
+
+        let mut workflows = vec![];
+        for name in &names {
+            let rules = &instructions[name.as_str()].rules;
+            let misplaced = || CompileError::MisplacedFallback(name.to_string());
+            let mut decision = match rules.last() {
+                Some(Rule::Jump(fallback)) => Decision::Go(target(fallback)?),
+                _ => return Err(misplaced()),
+            };
+            for rule in rules.iter().rev().skip(1) {
+                let Rule::Condition(category, operator, value, then) = rule else {
+                    return Err(misplaced());
+                };
+                let category = categories
+                    .index(category)
+                    .ok_or_else(|| CompileError::UnknownCategory(category.to_string()))?;
+                decision = Decision::Branch {
+                    condition: Condition {
+                        category,
+                        operator: *operator,
+                        value: *value,
+                    },
+                    then: target(then)?,
+                    otherwise: Box::new(decision),
+                };
+            }
+            workflows.push(Workflow {
+                name: name.to_string(),
+                decision,
+            });
+        }
+
+        let start = *index.get("in").ok_or(CompileError::MissingStart)?;
+        Ok(Program {
+            workflows,
+            start,
+            categories,
+            unused: unreachable_workflows(instructions),
+        })
+    }
+
+    fn accepts(&self, part: &Part) -> bool {
+        self.explain(part).1
+    }
+
+    /// The rules that fired for `part` in order, and whether it was accepted in the end.
+    fn explain(&self, part: &Part) -> (Vec<Step>, bool) {
+        let mut path = vec![];
+        let mut workflow = self.start;
+        loop {
+            let (rule, target) = self.workflows[workflow].decision.decide(part);
+            path.push((workflow, rule));
+            match target {
+                Target::Accept => return (path, true),
+                Target::Reject => return (path, false),
+                Target::Workflow(next) => workflow = next,
+            }
+        }
+    }
+
+    fn target_name(&self, target: Target) -> &str {
+        match target {
+            Target::Accept => "A",
+            Target::Reject => "R",
+            Target::Workflow(workflow) => &self.workflows[workflow].name,
+        }
+    }
+
+    /// A short reference to a rule, like `px#1`.
+    fn step_name(&self, (workflow, rule): Step) -> String {
+        format!("{}#{rule}", self.workflows[workflow].name)
+    }
+
+    /// A rule with its condition and target, like `px#1 m>2090 -> A`.
+    fn describe(&self, step: Step) -> String {
+        let name = self.step_name(step);
+        match self.workflows[step.0].decision.rule(step.1) {
+            Some(Decision::Go(target)) => format!("{name} -> {}", self.target_name(*target)),
+            Some(Decision::Branch {
+                condition, then, ..
+            }) => format!(
+                "{name} {}{}{} -> {}",
+                self.categories.names[condition.category],
+                condition.operator,
+                condition.value,
+                self.target_name(*then)
+            ),
+            None => format!("{name} (no such rule)"),
+        }
+    }
+
+    /// Walks all parts in `space` through the workflows and returns how many are accepted.
+    ///
+    /// `path` holds the rules that sent the parts to `target`, everything on the way is
+    /// reported to `visit`.
+    fn walk(
+        &self,
+        target: Target,
+        space: SolutionSpace,
+        path: &mut Vec<Step>,
+        visit: &mut impl FnMut(Visit),
+    ) -> u128 {
+        let workflow = match target {
+            Target::Accept => {
+                visit(Visit::Accepted {
+                    path,
+                    space: &space,
+                });
+                return space.volume();
+            }
+            Target::Reject => return 0,
+            Target::Workflow(workflow) => workflow,
+        };
+        let entered = space.volume();
+        let mut accepted = 0;
+        let mut decision = &self.workflows[workflow].decision;
+        let mut space = Some(space);
+        let mut rule = 0;
+        while let Some(arriving) = space.take() {
+            path.push((workflow, rule));
+            match decision {
+                Decision::Go(target) => {
+                    visit(Visit::Rule {
+                        step: (workflow, rule),
+                        split: false,
+                    });
+                    accepted += self.walk(*target, arriving, path, visit);
                 }
-                Rule::Jump(action) => {
-                    match action.as_str() {
-                        "A" => return true,
-                        "R" => return false,
-                        _ => inst = instructions.get(action),
+                Decision::Branch {
+                    condition,
+                    then,
+                    otherwise,
+                } => {
+                    let (yes, no) = arriving.split(condition);
+                    visit(Visit::Rule {
+                        step: (workflow, rule),
+                        split: yes.is_some() && no.is_some(),
+                    });
+                    if let Some(yes) = yes {
+                        accepted += self.walk(*then, yes, path, visit);
                     }
+                    space = no;
+                    decision = otherwise;
+                    rule += 1;
+                }
+            }
+            path.pop();
+        }
+        visit(Visit::Workflow {
+            workflow,
+            entered,
+            accepted,
+        });
+        accepted
+    }
+
+    fn count_accepted(&self, space: SolutionSpace) -> u128 {
+        self.walk(
+            Target::Workflow(self.start),
+            space,
+            &mut vec![],
+            &mut |_| {},
+        )
+    }
+
+    /// Every box of parts in `space` that ends up accepted, with the rules that led there.
+    fn accepted_regions(&self, space: SolutionSpace) -> Vec<Region> {
+        let mut regions = vec![];
+        let start = Target::Workflow(self.start);
+        self.walk(start, space, &mut vec![], &mut |visit| {
+            if let Visit::Accepted { path, space } = visit {
+                regions.push(Region {
+                    path: path.to_vec(),
+                    space: space.clone(),
+                });
+            }
+        });
+        regions
+    }
+
+    /// Runs all parts in `space` through the workflows and reports rules and workflows that
+    /// do not contribute to the result.
+    fn analyse(&self, space: SolutionSpace) -> Analysis {
+        let mut stats = Stats::default();
+        let start = Target::Workflow(self.start);
+        self.walk(start, space, &mut vec![], &mut |visit| match visit {
+            Visit::Rule { step, split } => {
+                let rule = stats.rules.entry(step).or_default();
+                rule.reached = true;
+                rule.split |= split;
+            }
+            Visit::Workflow {
+                workflow,
+                entered,
+                accepted,
+            } => {
+                let volumes = stats.volumes.entry(workflow).or_default();
+                volumes.0 += entered;
+                volumes.1 += accepted;
+            }
+            Visit::Accepted { .. } => {}
+        });
+
+        let mut analysis = Analysis {
+            unused: self.unused.clone(),
+            ..Analysis::default()
+        };
+        for (index, workflow) in self.workflows.iter().enumerate() {
+            let name = workflow.name.clone();
+            let Some(&(entered, accepted)) = stats.volumes.get(&index) else {
+                analysis.unreachable.push(name);
+                continue;
+            };
+            if accepted == entered {
+                analysis.always_accept.push(name.clone());
+            } else if accepted == 0 {
+                analysis.always_reject.push(name.clone());
+            }
+
+            let mut decision = &workflow.decision;
+            let mut rule = 0;
+            loop {
+                let rule_stats = stats.rules.get(&(index, rule)).copied().unwrap_or_default();
+                if !rule_stats.reached {
+                    analysis.dead_rules.push((name.clone(), rule));
+                }
+                let Decision::Branch {
+                    then, otherwise, ..
+                } = decision
+                else {
                     break;
+                };
+                // the condition does not matter if all arriving parts go the same way or
+                // both ways lead to the same place
+                let same_way = rule_stats.reached && !rule_stats.split;
+                let same_place = **otherwise == Decision::Go(*then);
+                if same_way || same_place {
+                    analysis.redundant_conditions.push((name.clone(), rule));
                 }
+                decision = otherwise;
+                rule += 1;
             }
         }
+        analysis
     }
-    unreachable!()
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let (instructions_str, parts_str) = input.split_once("\n\n").unwrap();
+/// A rule as the index of its workflow and its index within the workflow.
+type Step = (usize, usize);
 
-    let instructions = instructions_str
-        .lines()
-        .map(|line| line.parse::<Instruction>().unwrap())
-        .collect::<Vec<Instruction>>();
+/// What [`Program::walk`] reports along the way.
+enum Visit<'a> {
+    /// Parts arrived at a rule, `split` if its condition sent some of them either way.
+    Rule { step: Step, split: bool },
+    /// Parts entered a workflow, and `accepted` of the `entered` ones ended up accepted.
+    Workflow {
+        workflow: usize,
+        entered: u128,
+        accepted: u128,
+    },
+    /// The parts in `space` are accepted after taking `path`.
+    Accepted {
+        path: &'a [Step],
+        space: &'a SolutionSpace,
+    },
+}
 
-    let parts = parts_str
-        .lines()
-        .map(|line| line.parse::<Part>().unwrap())
-        .collect::<Vec<Part>>();
+/// What the analysis collects while walking the workflows.
+#[derive(Debug, Default)]
+struct Stats {
+    rules: HashMap<Step, RuleStats>,
+    /// The parts entering and accepted by each workflow.
+    volumes: HashMap<usize, (u128, u128)>,
+}
 
-    let instructions = instructions
-        .into_iter()
-        .map(|i| (i.name.clone(), i))
-        .collect::<HashMap<String, Instruction>>();
-    let accepted = parts.iter().filter(|part| process(part, &instructions));
+#[derive(Debug, Default, Clone, Copy)]
+struct RuleStats {
+    reached: bool,
+    /// Whether the condition ever sent some arriving parts one way and some the other.
+    split: bool,
+}
+
+/// Rules are given as workflow name and their index in the workflow.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Analysis {
+    /// Rules no part arrives at, because earlier rules already sent them elsewhere.
+    dead_rules: Vec<(String, usize)>,
+    /// Conditions that can be dropped without changing where any part ends up.
+    redundant_conditions: Vec<(String, usize)>,
+    /// Workflows no part arrives at.
+    unreachable: Vec<String>,
+    /// The unreachable workflows that no rule even refers to on the way from `in`.
+    unused: Vec<String>,
+    /// Workflows that accept every part arriving at them, possibly via other workflows.
+    always_accept: Vec<String>,
+    /// Workflows that reject every part arriving at them, possibly via other workflows.
+    always_reject: Vec<String>,
+}
+
+impl Display for Analysis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rules = |rules: &[(String, usize)]| {
+            rules
+                .iter()
+                .map(|(workflow, rule)| format!("{workflow}#{rule}"))
+                .join(", ")
+        };
+        writeln!(f, "Dead rules: {}", rules(&self.dead_rules))?;
+        writeln!(
+            f,
+            "Redundant conditions: {}",
+            rules(&self.redundant_conditions)
+        )?;
+        writeln!(f, "Unreachable workflows: {}", self.unreachable.join(", "))?;
+        writeln!(f, "Unused workflows: {}", self.unused.join(", "))?;
+        writeln!(f, "Always accepting: {}", self.always_accept.join(", "))?;
+        write!(f, "Always rejecting: {}", self.always_reject.join(", "))
+    }
+}
+
+/// The rules that fired for `part`, one per line.
+fn explain_part(program: &Program, part: &Part) -> String {
+    let (path, accepted) = program.explain(part);
+    let mut result = format!(
+        "{}: {}\n",
+        program.categories.format_part(part),
+        if accepted { "accepted" } else { "rejected" }
+    );
+    for step in path {
+        result.push_str(&format!("  {}\n", program.describe(step)));
+    }
+    result
+}
+
+pub fn part_one(input: &str) -> Option<u32> {
+    let (program, parts) = parse_input(input)?;
+    let accepted = parts.iter().filter(|part| program.accepts(part));
     let result: u32 = accepted.map(|part| part.score() as u32).sum();
     Some(result)
 }
 
+/// The parts with every rating within an inclusive range, in the order of [`Categories::names`].
 #[derive(PartialEq, Debug, Clone)]
 struct SolutionSpace {
-    variables: HashMap<String, (i32, i32)>,
+    ranges: Vec<Range>,
 }
 
 impl SolutionSpace {
-    fn new() -> Self {
+    /// All parts with ratings within the bounds of their categories.
+    fn new(categories: &Categories) -> Self {
         SolutionSpace {
-            variables: HashMap::from_iter([
-                ("x".to_string(), (1, 4000)),
-                ("m".to_string(), (1, 4000)),
-                ("a".to_string(), (1, 4000)),
-                ("s".to_string(), (1, 4000)),
-            ]),
+            ranges: categories.bounds.clone(),
         }
     }
 
-    fn get_number_of_solutions(&self) -> u64 {
-        self.variables
-            .values()
-            .map(|(l, u)| ((u - l + 1) as u64).max(0u64))
+    /// The number of parts, `0` if any range is empty.
+    fn volume(&self) -> u128 {
+        self.ranges
+            .iter()
+            .map(|&(l, u)| (i64::from(u) - i64::from(l) + 1).max(0) as u128)
             .product()
     }
+
+    /// The parts that do and do not match `condition`.
+    fn split(self, condition: &Condition) -> (Option<Self>, Option<Self>) {
+        let (yes, no) = condition.split(self.ranges[condition.category]);
+        let with = |range: Range| {
+            let mut space = self.clone();
+            space.ranges[condition.category] = range;
+            space
+        };
+        (yes.map(with), no.map(with))
+    }
 }
 
-fn reduce(
-    mut space: SolutionSpace,
-    instructions: &HashMap<String, Instruction>,
-    current: &str,
-    path: Vec<String>,
-) -> u64 {
-    match current {
-        "A" => space.get_number_of_solutions(),
-        "R" => 0,
-        rule => {
-            let instruction = instructions.get(rule).unwrap();
-            let mut sum = 0u64;
-            for r in &instruction.rules {
-                match r {
-                    Rule::Condition(variable, operator, value, target) => {
-                        let mut yes_space = space.clone();
-                        let yes_range = yes_space.variables.get_mut(variable).unwrap();
-                        match operator.as_str() {
-                            ">" => {
-                                yes_range.0 = std::cmp::max(yes_range.0, *value + 1);
-                                let no_range = space.variables.get_mut(variable).unwrap();
-                                no_range.1 = std::cmp::min(no_range.1, *value);
-                            }
-                            "<" => {
-                                yes_range.1 = std::cmp::min(yes_range.1, *value - 1);
-                                let no_range = space.variables.get_mut(variable).unwrap();
-                                no_range.0 = std::cmp::max(no_range.0, *value);
-                            }
-                            _ => panic!("Unknown operator {}", operator),
-                        }
-
-                        // yes jump
-                        let mut path = path.clone();
-                        path.push(target.clone());
-                        sum += reduce(yes_space, instructions, target.as_str(), path);
-                    }
-                    Rule::Jump(action) => {
-                        let mut path = path.clone();
-                        path.push(action.clone());
-                        sum += reduce(space.clone(), instructions, action.as_str(), path);
-                    }
-                }
+/// A box of parts that is accepted, and the rules that led there.
+#[derive(PartialEq, Debug, Clone)]
+struct Region {
+    path: Vec<Step>,
+    space: SolutionSpace,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum RegionFormat {
+    Text,
+    Csv,
+}
+
+impl FromStr for RegionFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(RegionFormat::Text),
+            "csv" => Ok(RegionFormat::Csv),
+            _ => Err(format!("unknown format {s}, expecting text or csv")),
+        }
+    }
+}
+
+fn render_regions(program: &Program, regions: &[Region], format: RegionFormat) -> String {
+    let path = |region: &Region| {
+        region
+            .path
+            .iter()
+            .map(|step| program.step_name(*step))
+            .join(" > ")
+    };
+    let mut result = String::new();
+    match format {
+        RegionFormat::Text => {
+            for region in regions {
+                let ranges = program
+                    .categories
+                    .names
+                    .iter()
+                    .zip(&region.space.ranges)
+                    .map(|(category, (low, high))| format!("{category}={low}..{high}"))
+                    .join(" ");
+                result.push_str(&format!(
+                    "{ranges} (volume {}): {}\n",
+                    region.space.volume(),
+                    path(region)
+                ));
+            }
+        }
+        RegionFormat::Csv => {
+            let header = program
+                .categories
+                .names
+                .iter()
+                .map(|category| format!("{category}_min,{category}_max"))
+                .join(",");
+            result.push_str(&format!("path,{header},volume\n"));
+            for region in regions {
+                let ranges = region
+                    .space
+                    .ranges
+                    .iter()
+                    .map(|(low, high)| format!("{low},{high}"))
+                    .join(",");
+                result.push_str(&format!(
+                    "{},{ranges},{}\n",
+                    path(region),
+                    region.space.volume()
+                ));
             }
-            sum
         }
     }
+    result
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let (instructions_str, _) = input.split_once("\n\n").unwrap();
-    let instructions = instructions_str
-        .lines()
-        .map(|line| line.parse::<Instruction>().unwrap())
-        .collect::<Vec<Instruction>>();
+    let (program, _) = parse_input(input)?;
+    let space = SolutionSpace::new(&program.categories);
+    u64::try_from(program.count_accepted(space)).ok()
+}
 
-    let instructions = instructions
-        .into_iter()
-        .map(|i| (i.name.clone(), i))
-        .collect::<HashMap<String, Instruction>>();
+/// Explains `part` if given, every part of `parts` otherwise.
+fn explain_parts(
+    program: &Program,
+    parts: &[Part],
+    part: Option<&str>,
+) -> Result<String, ParseError> {
+    match part {
+        Some(part) => Ok(explain_part(program, &program.categories.parse_part(part)?)),
+        None => Ok(parts
+            .iter()
+            .map(|part| explain_part(program, part))
+            .collect()),
+    }
+}
 
-    let path = vec!["in".to_string()];
-    Some(reduce(SolutionSpace::new(), &instructions, "in", path))
+fn extras(input: &str) {
+    let Some((program, parts)) = parse_input(input) else {
+        return;
+    };
+    let space = SolutionSpace::new(&program.categories);
+
+    // `--explain ["{x=..,m=..}"]` shows the way of the given part through the workflows, or
+    // that of every part in the input without one
+    let args = std::env::args().collect::<Vec<_>>();
+    if let Some(i) = args.iter().position(|arg| arg == "--explain") {
+        let part = args.get(i + 1).filter(|arg| arg.starts_with('{'));
+        match explain_parts(&program, &parts, part.map(String::as_str)) {
+            Ok(explanation) => print!("{explanation}"),
+            Err(e) => eprintln!("Ignoring --explain: {e}"),
+        }
+    }
+
+    // `--regions <text|csv>` lists the accepted boxes of parts
+    let mut args = pico_args::Arguments::from_env();
+    match args.opt_value_from_str::<_, RegionFormat>("--regions") {
+        Ok(Some(format)) => {
+            let regions = program.accepted_regions(space.clone());
+            print!("{}", render_regions(&program, &regions, format));
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("Ignoring --regions: {e}"),
+    }
+
+    // `--analyse` reports rules and workflows that could be simplified
+    if std::env::args().any(|arg| arg == "--analyse") {
+        println!("{}", program.analyse(space));
+    }
 }
 
 #[cfg(test)]
@@ -258,9 +977,9 @@ mod tests {
             Instruction {
                 name: "ex".to_string(),
                 rules: vec![
-                    Rule::Condition("x".to_string(), ">".to_string(), 10, "one".to_string()),
-                    Rule::Condition("m".to_string(), "<".to_string(), 20, "two".to_string()),
-                    Rule::Condition("a".to_string(), ">".to_string(), 30, "R".to_string()),
+                    Rule::Condition("x".to_string(), Operator::Greater, 10, "one".to_string()),
+                    Rule::Condition("m".to_string(), Operator::Less, 20, "two".to_string()),
+                    Rule::Condition("a".to_string(), Operator::Greater, 30, "R".to_string()),
                     Rule::Jump("A".to_string()),
                     Rule::Jump("R".to_string()),
                 ]
@@ -270,20 +989,284 @@ mod tests {
 
     #[test]
     fn test_parse_part() {
+        let categories = Categories::new(["x", "m", "a", "s"]);
         let line = "{x=787,m=2655,a=1222,s=2876}";
-        let part = line.parse::<Part>().unwrap();
+        let part = categories.parse_part(line).unwrap();
 
         assert_eq!(
             part,
             Part {
-                variables: HashMap::from_iter([
-                    ("x".to_string(), 787),
-                    ("m".to_string(), 2655),
-                    ("a".to_string(), 1222),
-                    ("s".to_string(), 2876),
-                ])
+                values: vec![Some(787), Some(2655), Some(1222), Some(2876)]
             }
         );
+        assert_eq!(categories.format_part(&part), line);
+
+        // any order works, and categories can be left out, but not rated twice
+        let part = categories.parse_part("{s=4,a=3,m=2,x=1}").unwrap();
+        assert_eq!(part.values, [Some(1), Some(2), Some(3), Some(4)]);
+        let part = categories.parse_part("{x=1,a=3}").unwrap();
+        assert_eq!(part.values, [Some(1), None, Some(3), None]);
+        assert_eq!(part.score(), 4);
+        assert_eq!(categories.format_part(&part), "{x=1,a=3}");
+        let error = |line| categories.parse_part(line).unwrap_err().to_string();
+        assert_eq!(error("{x=1,m=2,a=3,s=4,y=5}"), "1:18: unknown category y");
+        assert_eq!(error("{x=1,m=2,a=3,s=4,x=5}"), "1:18: x is rated twice");
+    }
+
+    #[test]
+    fn test_categories() {
+        let [instructions, parts] =
+            parse::sections_n("in{z>5:A,b<3:R,A}\n\n{b=1,a=2}\n{c=3,a=4,b=5}").unwrap();
+        let instructions = parse_instructions(&instructions).unwrap();
+        let categories = Categories::infer(&instructions, &parts).unwrap();
+        assert_eq!(categories, Categories::new(["b", "a", "c", "z"]));
+
+        let configured = configure_categories(categories.clone(), None, Some("0..9")).unwrap();
+        assert_eq!(configured.bounds, [(0, 9); 4]);
+        let configured =
+            configure_categories(categories.clone(), Some("p,q"), Some("q=5..6")).unwrap();
+        assert_eq!(configured.names, ["p", "q"]);
+        assert_eq!(configured.bounds, [(1, 4000), (5, 6)]);
+        assert!(configure_categories(categories, None, Some("y=1..2")).is_err());
+
+        // parts rated in different categories, conditions on unrated ones do not match
+        assert_eq!(part_one("in{a<3:A,R}\n\n{b=1,a=2}\n{c=3,a=4,b=5}"), Some(3));
+        assert_eq!(part_one("in{x<3:A,R}\n\n{x=1,m=2}\n{x=2}"), Some(5));
+        // every category still spans its bounds in the solution space
+        assert_eq!(part_two("in{x<3:A,R}\n\n{x=1,m=2}\n{x=2}"), Some(2 * 4000));
+        assert_eq!(
+            part_one("in{z>5:R,A}\n\n{b=1,a=2}\n{c=3,a=4,b=5}"),
+            Some(15)
+        );
+    }
+
+    #[test]
+    fn test_workflow_graph() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let [instructions, _] = parse::sections_n(&input).unwrap();
+        let mut instructions = parse_instructions(&instructions).unwrap();
+        assert!(unreachable_workflows(&instructions).is_empty());
+        assert!(workflow_cycles(&instructions).is_empty());
+
+        for line in ["lost{a<5:R,A}", "loop{x>10:crn,A}", "crn{x>2662:A,loop}"] {
+            let instruction = line.parse::<Instruction>().unwrap();
+            instructions.insert(instruction.name.clone(), instruction);
+        }
+        // crn is reachable from `in`, so its cycle with the new workflow is as well
+        assert_eq!(unreachable_workflows(&instructions), ["lost"]);
+        assert_eq!(workflow_cycles(&instructions), [["crn", "loop"]]);
+    }
+
+    #[test]
+    fn test_parse_unknown_operator() {
+        let error = "ex{x=10:one,A}".parse::<Instruction>().unwrap_err();
+        assert_eq!(error.to_string(), "1:5: unknown operator =");
+        let error = "ex{x>10:one,m<99999999999:A,A}"
+            .parse::<Instruction>()
+            .unwrap_err();
+        assert_eq!((error.line, error.column), (1, 15));
+    }
+
+    fn compile(lines: &str) -> Result<Program, CompileError> {
+        let [instructions] = parse::sections_n(lines).unwrap();
+        let categories = Categories::new(["x", "m", "a", "s"]);
+        Program::compile(&parse_instructions(&instructions).unwrap(), categories)
+    }
+
+    fn full_space(program: &Program) -> SolutionSpace {
+        SolutionSpace::new(&program.categories)
+    }
+
+    #[test]
+    fn test_dimensions() {
+        // a single category, and eight of them
+        let [instructions, parts] = parse::sections_n("in{q<11:A,R}\n\n{q=3}").unwrap();
+        let instructions = parse_instructions(&instructions).unwrap();
+        let categories = Categories::infer(&instructions, &parts).unwrap();
+        let program = Program::compile(&instructions, categories).unwrap();
+        assert_eq!(program.count_accepted(full_space(&program)), 10);
+
+        let categories = Categories::new(["a", "b", "c", "d", "e", "f", "g", "h"]);
+        let program = Program::compile(&instructions_of("in{h>2000:R,A}"), categories).unwrap();
+        assert_eq!(
+            program.count_accepted(full_space(&program)),
+            4000u128.pow(7) * 2000
+        );
+        let part = program
+            .categories
+            .parse_part("{a=1,b=2,c=3,d=4,e=5,f=6,g=7,h=8}")
+            .unwrap();
+        assert!(program.accepts(&part));
+        assert_eq!(part.score(), 36);
+    }
+
+    #[test]
+    fn test_extreme_bounds() {
+        let categories = configure_categories(
+            Categories::new(["x"]),
+            None,
+            Some("-2147483648..2147483647"),
+        )
+        .unwrap();
+        let program =
+            Program::compile(&instructions_of("in{x>2147483647:R,A}"), categories).unwrap();
+        assert_eq!(full_space(&program).volume(), 1 << 32);
+        assert_eq!(program.count_accepted(full_space(&program)), 1 << 32);
+
+        let below_min = Condition {
+            category: 0,
+            operator: Operator::Less,
+            value: i32::MIN,
+        };
+        let all = (i32::MIN, i32::MAX);
+        assert_eq!(below_min.split(all), (None, Some(all)));
+        let above = Condition {
+            operator: Operator::Greater,
+            ..below_min
+        };
+        assert_eq!(
+            above.split(all),
+            (Some((i32::MIN + 1, i32::MAX)), Some((i32::MIN, i32::MIN)))
+        );
+    }
+
+    fn instructions_of(lines: &str) -> HashMap<String, Instruction> {
+        let [instructions] = parse::sections_n(lines).unwrap();
+        parse_instructions(&instructions).unwrap()
+    }
+
+    #[test]
+    fn test_compile() {
+        let program = compile("in{x<10:A,a>5:out,R}\nout{R}").unwrap();
+        assert_eq!(program.start, 0);
+        assert_eq!(
+            program.workflows[0].decision,
+            Decision::Branch {
+                condition: Condition {
+                    category: 0,
+                    operator: Operator::Less,
+                    value: 10
+                },
+                then: Target::Accept,
+                otherwise: Box::new(Decision::Branch {
+                    condition: Condition {
+                        category: 2,
+                        operator: Operator::Greater,
+                        value: 5
+                    },
+                    then: Target::Workflow(1),
+                    otherwise: Box::new(Decision::Go(Target::Reject)),
+                }),
+            }
+        );
+
+        assert_eq!(compile("start{A}"), Err(CompileError::MissingStart));
+        assert_eq!(
+            compile("in{x<10:out,A}"),
+            Err(CompileError::UnknownWorkflow("out".to_string()))
+        );
+        assert_eq!(
+            compile("in{y<10:A,R}"),
+            Err(CompileError::UnknownCategory("y".to_string()))
+        );
+        assert_eq!(
+            compile("in{x<10:A}"),
+            Err(CompileError::MisplacedFallback("in".to_string()))
+        );
+        assert_eq!(
+            compile("in{A,x<10:R,R}"),
+            Err(CompileError::MisplacedFallback("in".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_analyse() {
+        let program = compile(
+            "in{x<10:low,x>5:high,R}
+low{x>20:A,m<5:A,R}
+high{a>5:A,A}
+gone{R}",
+        )
+        .unwrap();
+        let analysis = program.analyse(full_space(&program));
+        assert_eq!(
+            analysis,
+            Analysis {
+                // every part reaching `in#2` has been sent to `low` or `high` already
+                dead_rules: vec![("in".to_string(), 2)],
+                redundant_conditions: vec![
+                    ("high".to_string(), 0),
+                    ("in".to_string(), 1),
+                    ("low".to_string(), 0),
+                ],
+                unreachable: vec!["gone".to_string()],
+                unused: vec!["gone".to_string()],
+                always_accept: vec!["high".to_string()],
+                always_reject: vec![],
+            }
+        );
+
+        let example = advent_of_code::template::read_file("examples", DAY);
+        let (program, _) = parse_input(&example).unwrap();
+        let analysis = program.analyse(full_space(&program));
+        assert_eq!(analysis.always_reject, ["gd"]);
+        assert!(analysis.always_accept.contains(&"lnx".to_string()));
+        assert!(analysis
+            .redundant_conditions
+            .contains(&("gd".to_string(), 0)));
+    }
+
+    #[test]
+    fn test_explain() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let (program, parts) = parse_input(&input).unwrap();
+        let part = &parts[0];
+        assert_eq!(
+            explain_part(&program, part),
+            "{x=787,m=2655,a=1222,s=2876}: accepted
+  in#1 -> qqz
+  qqz#0 s>2770 -> qs
+  qs#1 -> lnx
+  lnx#0 m>1548 -> A
+"
+        );
+        assert!(!program.accepts(&parts[1]));
+
+        // a part given with `--explain`, or all of them without one
+        let explanation = explain_parts(&program, &parts, Some("{x=787,m=2655,a=1222,s=2876}"));
+        assert_eq!(explanation.unwrap(), explain_part(&program, part));
+        let explanation = explain_parts(&program, &parts, None).unwrap();
+        assert_eq!(
+            explanation.lines().filter(|l| l.starts_with('{')).count(),
+            5
+        );
+        let error = explain_parts(&program, &parts, Some("{x=1,q=2}")).unwrap_err();
+        assert_eq!(error.column, 6);
+    }
+
+    #[test]
+    fn test_accepted_regions() {
+        let program = compile("in{x<10:low,A}\nlow{m>5:A,R}").unwrap();
+        let regions = program.accepted_regions(full_space(&program));
+        assert_eq!(
+            render_regions(&program, &regions, RegionFormat::Text),
+            "x=1..9 m=6..4000 a=1..4000 s=1..4000 (volume 575280000000): in#0 > low#0
+x=10..4000 m=1..4000 a=1..4000 s=1..4000 (volume 255424000000000): in#1
+"
+        );
+        assert_eq!(
+            render_regions(&program, &regions, RegionFormat::Csv),
+            "path,x_min,x_max,m_min,m_max,a_min,a_max,s_min,s_max,volume
+in#0 > low#0,1,9,6,4000,1,4000,1,4000,575280000000
+in#1,10,4000,1,4000,1,4000,1,4000,255424000000000
+"
+        );
+
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let (program, _) = parse_input(&input).unwrap();
+        let regions = program.accepted_regions(full_space(&program));
+        let total: u128 = regions.iter().map(|r| r.space.volume()).sum();
+        assert_eq!(total, 167409079868000);
     }
 
     // 10354173786531281 too high
@@ -3,9 +3,14 @@ use std::{
     ops::Not,
 };
 
+use advent_of_code::{
+    graph::{Digraph, GraphExport, GraphFormat, NodeShape},
+    math,
+    parse::{self, ParseError},
+};
 use itertools::Itertools;
 
-advent_of_code::solution!(20);
+advent_of_code::solution!(20, extras = extras);
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum Module {
@@ -14,13 +19,15 @@ enum Module {
     Broadcaster(),
 }
 
-fn parse(input: &str) -> (HashMap<String, Module>, HashMap<String, Vec<String>>) {
+type Parsed = (HashMap<String, Module>, HashMap<String, Vec<String>>);
+
+fn parse(input: &str) -> Result<Parsed, ParseError> {
     let mut modules = HashMap::new();
     let mut adjacencies = HashMap::new();
-    for l in input.lines() {
-        let (l, r) = l.split_once("->").unwrap();
+    let lines = parse::parse_lines(input, |line| {
+        let (l, r) = parse::split_once(line, "->")?;
         let (l, r) = (l.trim(), r.trim());
-        let (name, module) = match l {
+        let module = match l {
             "broadcaster" => ("broadcaster".to_string(), Module::Broadcaster()),
             name if name.starts_with('%') => (
                 name.trim_start_matches('%').to_string(),
@@ -34,11 +41,12 @@ fn parse(input: &str) -> (HashMap<String, Module>, HashMap<String, Vec<String>>)
                     Some(false),
                 ),
             ),
-            _ => {
-                dbg!(&l);
-                unreachable!();
-            }
+            _ => return Err(ParseError::at(line, l, format!("unknown module `{l}`"))),
         };
+        Ok((module, r))
+    })?;
+
+    for ((name, module), r) in lines {
         adjacencies.insert(
             name.to_string(),
             r.split(',').map(|it| it.trim().to_string()).collect_vec(),
@@ -54,7 +62,7 @@ fn parse(input: &str) -> (HashMap<String, Module>, HashMap<String, Vec<String>>)
         }
     }
 
-    (modules, adjacencies)
+    Ok((modules, adjacencies))
 }
 
 #[derive(Debug)]
@@ -73,20 +81,18 @@ impl Counter {
     }
 }
 
+/// Presses the button once and reports every pulse `(from, to, signal)` to `observer`,
+/// in the order the pulses are processed.
 fn simulate(
     modules: &mut HashMap<String, Module>,
     adjacencies: &HashMap<String, Vec<String>>,
-    counter: &mut Counter,
-    i: u32,
-    memory: &mut HashMap<&str, Vec<u32>>,
-) -> u32 {
+    observer: &mut impl FnMut(&str, &str, bool),
+) {
     // from, to, signal
     let mut pending: VecDeque<(&str, &str, bool)> = VecDeque::new();
     pending.push_back(("button", "broadcaster", false));
-    counter.count(&false, 1);
-    let mut lows_to_rx = 0;
     while let Some((from, to, sig)) = pending.pop_front() {
-        // println!("{} -{} -> {}", from, sig, to);
+        observer(from, to, sig);
         if let Some(module) = modules.get_mut(to) {
             let connected = adjacencies.get(to).expect(to);
             match module {
@@ -95,9 +101,8 @@ fn simulate(
                     for t in connected {
                         pending.push_back((to, t, *state));
                     }
-                    counter.count(state, connected.len());
                 }
-                Module::Conjunction(name, state, output) => {
+                Module::Conjunction(_, state, output) => {
                     if let Some(memory) = state.get_mut(from) {
                         *memory = sig;
                     } else {
@@ -105,128 +110,579 @@ fn simulate(
                     }
                     let next_sig = state.values().all(|it| *it).not();
 
-                    match name.as_str() {
-                        "rz" | "kv" | "jg" | "mr" if next_sig => {
-                            if let Some(existing) = memory.get_mut(name.as_str()) {
-                                existing.push(i);
-                            }
-                        }
-                        _ => {}
-                    }
-
                     output.replace(next_sig);
                     for t in connected {
                         pending.push_back((to, t, next_sig));
                     }
-                    counter.count(&next_sig, connected.len());
                 }
                 Module::Broadcaster() => {
                     connected
                         .iter()
                         .for_each(|it| pending.push_back((to, it, sig)));
-                    counter.count(&sig, connected.len());
                 }
-                _ => {} //println!("IGNORED {} -> {} = {}", from, to, sig),
+                _ => {} // flip-flops ignore high pulses
             }
-        } else if to == "rx" && !sig {
-            lows_to_rx += 1;
         }
     }
-    lows_to_rx
 }
 
-fn mermaid(adjacencies: &HashMap<String, Vec<String>>) -> String {
-    let mut result = String::new();
-    for (name, connected) in adjacencies {
-        for m in connected {
-            result.push_str(&format!("{} --> {}\n", name, m));
-        }
-    }
-    result
+/// A single pulse, `time` counts all pulses since the first press.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PulseEvent {
+    press: u32,
+    time: u64,
+    from: String,
+    to: String,
+    signal: bool,
 }
 
-pub fn part_one(input: &str) -> Option<u64> {
-    let (mut modules, adjacencies) = parse(input);
-    let mut counter = Counter { lows: 0, highs: 0 };
-    (0..1000).for_each(|_| {
-        simulate(
-            &mut modules,
-            &adjacencies,
-            &mut counter,
-            0,
-            &mut HashMap::new(),
-        );
-    });
-    Some(counter.highs as u64 * counter.lows as u64)
+/// Records the pulses of a number of button presses.
+#[derive(Debug, Default)]
+struct Trace {
+    /// only pulses from or to these modules are recorded, all if empty
+    filter: Vec<String>,
+    events: Vec<PulseEvent>,
+    time: u64,
 }
 
-fn lcm(elements: Vec<u32>) -> u64 {
-    let mut elements = elements;
-    let mut result = 1;
-    let mut divisor = 2;
+impl Trace {
+    fn with_filter(filter: Vec<String>) -> Self {
+        Trace {
+            filter,
+            ..Default::default()
+        }
+    }
+
+    fn is_traced(&self, module: &str) -> bool {
+        self.filter.is_empty() || self.filter.iter().any(|it| it == module)
+    }
 
-    loop {
-        let mut divisible = false;
-        for i in 0..elements.len() {
-            if elements[i] % divisor == 0 {
-                divisible = true;
-                elements[i] = elements[i] / divisor;
+    fn record(&mut self, press: u32, from: &str, to: &str, signal: bool) {
+        if self.is_traced(from) || self.is_traced(to) {
+            self.events.push(PulseEvent {
+                press,
+                time: self.time,
+                from: from.to_string(),
+                to: to.to_string(),
+                signal,
+            });
+        }
+        self.time += 1;
+    }
+
+    /// Exports the trace as Value Change Dump, e.g. for GTKWave.
+    ///
+    /// Every traced module becomes a wire carrying the last signal it sent. For flip-flops that
+    /// is their state, for conjunctions their output. Each pulse takes one time unit, `press`
+    /// shows the current button press.
+    fn to_vcd(&self, modules: &HashMap<String, Module>) -> String {
+        let wires = modules
+            .keys()
+            .filter(|name| self.is_traced(name))
+            .sorted()
+            .enumerate()
+            .map(|(i, name)| (name.as_str(), vcd_identifier(i + 1)))
+            .collect::<HashMap<_, _>>();
+        let press_id = vcd_identifier(0);
+
+        let mut vcd = String::new();
+        vcd.push_str("$timescale 1ns $end\n$scope module circuit $end\n");
+        vcd.push_str(&format!("$var integer 32 {press_id} press $end\n"));
+        for (name, id) in wires.iter().sorted() {
+            vcd.push_str(&format!("$var wire 1 {id} {name} $end\n"));
+        }
+        vcd.push_str("$upscope $end\n$enddefinitions $end\n");
+
+        // flip-flops start switched off, conjunctions are unknown until they first send
+        vcd.push_str(&format!("$dumpvars\nb0 {press_id}\n"));
+        for (name, id) in wires.iter().sorted() {
+            let initial = match modules.get(*name) {
+                Some(Module::FlipFlop(state)) => u8::from(*state).to_string(),
+                _ => "x".to_string(),
+            };
+            vcd.push_str(&format!("{initial}{id}\n"));
+        }
+        vcd.push_str("$end\n");
+
+        let mut values: HashMap<&str, bool> = HashMap::new();
+        let mut press = 0;
+        let mut time = None;
+        for event in &self.events {
+            let mut changes = Vec::new();
+            if event.press != press {
+                press = event.press;
+                changes.push(format!("b{press:b} {press_id}"));
+            }
+            if let Some(id) = wires.get(event.from.as_str()) {
+                if values.insert(event.from.as_str(), event.signal) != Some(event.signal) {
+                    changes.push(format!("{}{id}", u8::from(event.signal)));
+                }
+            }
+            if !changes.is_empty() {
+                if time != Some(event.time) {
+                    time = Some(event.time);
+                    vcd.push_str(&format!("#{}\n", event.time));
+                }
+                changes.iter().for_each(|c| {
+                    vcd.push_str(c);
+                    vcd.push('\n');
+                });
             }
         }
+        vcd.push_str(&format!("#{}\n", self.time));
+        vcd
+    }
+}
 
-        if divisible {
-            result = result * (divisor as u64);
-        } else {
-            divisor += 1;
+/// VCD identifiers are made of the printable characters `!` to `~`.
+fn vcd_identifier(index: usize) -> String {
+    let mut index = index;
+    let mut id = String::new();
+    loop {
+        id.push((b'!' + (index % 94) as u8) as char);
+        index /= 94;
+        if index == 0 {
+            return id;
         }
+        index -= 1;
+    }
+}
+
+/// `--vcd <file>` writes a waveform of the presses of part one to `file`,
+/// `--vcd-filter <module>,<module>` limits it to the given modules.
+fn vcd_options() -> Option<(String, Vec<String>)> {
+    let mut args = pico_args::Arguments::from_env();
+    let file = args.opt_value_from_str::<_, String>("--vcd").ok()??;
+    let filter = args
+        .opt_value_from_str::<_, String>("--vcd-filter")
+        .ok()
+        .flatten()
+        .map(|it| it.split(',').map(|m| m.trim().to_string()).collect())
+        .unwrap_or_default();
+    Some((file, filter))
+}
 
-        if elements.iter().all(|it| *it == 1) {
-            return result;
+/// The circuit as a graph, modules are shaped by kind and modules without outputs (like `rx`)
+/// are drawn as circles.
+fn circuit_graph(
+    modules: &HashMap<String, Module>,
+    adjacencies: &HashMap<String, Vec<String>>,
+) -> GraphExport {
+    let mut graph = GraphExport::new("circuit");
+    for (from, connected) in adjacencies {
+        for to in connected {
+            graph.edge(from, to);
+        }
+    }
+    for (name, module) in modules {
+        let (label, shape) = match module {
+            Module::FlipFlop(_) => (format!("%{name}"), NodeShape::Box),
+            Module::Conjunction(..) => (format!("&{name}"), NodeShape::Diamond),
+            Module::Broadcaster() => (name.clone(), NodeShape::Hexagon),
+        };
+        graph.node(name, &label, shape);
+    }
+    for sink in adjacencies.values().flatten() {
+        if !modules.contains_key(sink) {
+            graph.node(sink, sink, NodeShape::Circle);
         }
     }
+    graph
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    let (mut modules, adjacencies) = parse(input);
+/// `--export-graph <mermaid|dot|graphml>` prints the circuit in the given format.
+fn export_graph_option() -> Option<GraphFormat> {
+    let mut args = pico_args::Arguments::from_env();
+    match args.opt_value_from_str::<_, GraphFormat>("--export-graph") {
+        Ok(format) => format,
+        Err(e) => {
+            eprintln!("Ignoring --export-graph: {e}");
+            None
+        }
+    }
+}
 
-    // print!("{}", mermaid(&adjacencies));
+/// Presses the button 1000 times and counts the pulses, recording them into `trace` if given.
+fn press_button(
+    modules: &mut HashMap<String, Module>,
+    adjacencies: &HashMap<String, Vec<String>>,
+    mut trace: Option<&mut Trace>,
+) -> Counter {
     let mut counter = Counter { lows: 0, highs: 0 };
+    (1..=1000).for_each(|press| {
+        simulate(modules, adjacencies, &mut |from, to, sig| {
+            counter.count(&sig, 1);
+            if let Some(trace) = trace.as_mut() {
+                trace.record(press, from, to, sig);
+            }
+        });
+    });
+    counter
+}
 
-    let mut conjunctions: HashMap<&str, Vec<u32>> = HashMap::from_iter([
-        ("rz", vec![]),
-        ("kv", vec![]),
-        ("jg", vec![]),
-        ("mr", vec![]),
-    ]);
-
-    for i in 1..100000 {
-        simulate(
-            &mut modules,
-            &adjacencies,
-            &mut counter,
-            i,
-            &mut conjunctions,
-        );
+pub fn part_one(input: &str) -> Option<u64> {
+    let (mut modules, adjacencies) = parse(input).ok()?;
+    let counter = press_button(&mut modules, &adjacencies, None);
+    Some(counter.highs as u64 * counter.lows as u64)
+}
+
+/// Why the number of presses until a module receives a low pulse could not be determined.
+#[derive(Debug, PartialEq, Eq)]
+enum CircuitError {
+    /// No module sends pulses to the target.
+    Unreachable(String),
+    /// The module is not fed by a single conjunction and did not receive a low pulse within
+    /// [`PRESS_LIMIT`] presses.
+    UnsupportedFeeder(String),
+    /// An input of the feeding conjunction did not send high pulses periodically within
+    /// [`PRESS_LIMIT`] presses.
+    NoPeriod(String),
+    /// The inputs of the feeding conjunction never send high pulses in the same press.
+    NoCommonPress,
+}
+
+const PRESS_LIMIT: u32 = 100_000;
+
+/// How often every input of `conjunction` sent a high pulse to it, as `(first, period)`.
+///
+/// Presses the button until every input has fired three times, so the period is confirmed once.
+fn find_cycles(
+    modules: &mut HashMap<String, Module>,
+    adjacencies: &HashMap<String, Vec<String>>,
+    conjunction: &str,
+) -> Result<Vec<(String, u32, u32)>, CircuitError> {
+    let inputs = match modules.get(conjunction) {
+        Some(Module::Conjunction(_, state, _)) => state.keys().cloned().sorted().collect_vec(),
+        _ => return Err(CircuitError::UnsupportedFeeder(conjunction.to_string())),
+    };
 
-        if conjunctions.values().all(|it| it.len() >= 2) {
+    let mut firings: HashMap<String, Vec<u32>> = HashMap::new();
+    for press in 1..=PRESS_LIMIT {
+        simulate(modules, adjacencies, &mut |from, to, sig| {
+            if sig && to == conjunction {
+                let presses = firings.entry(from.to_string()).or_default();
+                if presses.last() != Some(&press) {
+                    presses.push(press);
+                }
+            }
+        });
+        if inputs
+            .iter()
+            .all(|i| firings.get(i).is_some_and(|it| it.len() >= 3))
+        {
             break;
         }
     }
 
-    dbg!(&conjunctions);
-    let steps = conjunctions.values().map(|it| it[1] - it[0]).collect_vec();
+    inputs
+        .into_iter()
+        .map(|input| match firings.get(&input).map(|it| it.as_slice()) {
+            Some([first, second, third, ..]) if second - first == third - second => {
+                Ok((input, *first, second - first))
+            }
+            _ => Err(CircuitError::NoPeriod(input)),
+        })
+        .collect()
+}
+
+/// The fewest button presses until `target` receives a low pulse.
+///
+/// Real inputs feed the target from a single conjunction, whose inputs are independent
+/// sub-circuits that each send a high pulse periodically. The target receives a low pulse once
+/// all of them fire in the same press. Circuits without that structure are simulated until the
+/// target receives a low pulse or [`PRESS_LIMIT`] is reached.
+fn presses_until_low(
+    modules: &HashMap<String, Module>,
+    adjacencies: &HashMap<String, Vec<String>>,
+    target: &str,
+) -> Result<u64, CircuitError> {
+    let graph = Digraph::from_adjacencies(adjacencies);
+    let feeders = graph
+        .id(target)
+        .map(|id| graph.names(graph.predecessors(id)))
+        .unwrap_or_default();
+
+    let error = match feeders[..] {
+        [] => return Err(CircuitError::Unreachable(target.to_string())),
+        [feeder] => match find_cycles(&mut modules.clone(), adjacencies, feeder) {
+            Ok(cycles) => {
+                return first_common_press(cycles.into_iter().map(|(_, f, p)| (f, p)))
+                    .ok_or(CircuitError::NoCommonPress)
+            }
+            Err(error) => error,
+        },
+        _ => CircuitError::UnsupportedFeeder(target.to_string()),
+    };
+
+    // not the expected structure, fall back to simulating
+    let mut state = modules.clone();
+    for press in 1..=PRESS_LIMIT {
+        let mut reached = false;
+        simulate(&mut state, adjacencies, &mut |_, to, sig| {
+            reached |= to == target && !sig;
+        });
+        if reached {
+            return Ok(press as u64);
+        }
+    }
+    Err(error)
+}
+
+/// The sub-circuits behind the inputs of the conjunction that feeds `target`, as the input and
+/// every module that can send pulses towards it. The broadcaster is shared by all of them and
+/// left out.
+fn subcircuits(
+    modules: &HashMap<String, Module>,
+    adjacencies: &HashMap<String, Vec<String>>,
+    target: &str,
+) -> Result<Vec<(String, Vec<String>)>, CircuitError> {
+    let graph = Digraph::from_adjacencies(adjacencies);
+    let target_id = graph
+        .id(target)
+        .ok_or_else(|| CircuitError::Unreachable(target.to_string()))?;
+    let feeder = match graph.predecessors(target_id) {
+        [] => return Err(CircuitError::Unreachable(target.to_string())),
+        [feeder] => *feeder,
+        _ => return Err(CircuitError::UnsupportedFeeder(target.to_string())),
+    };
+    if !matches!(
+        modules.get(graph.name(feeder)),
+        Some(Module::Conjunction(..))
+    ) {
+        return Err(CircuitError::UnsupportedFeeder(
+            graph.name(feeder).to_string(),
+        ));
+    }
+    Ok(graph
+        .predecessors(feeder)
+        .iter()
+        .map(|&input| {
+            let modules = graph
+                .ancestors([input])
+                .into_iter()
+                .map(|id| graph.name(id).to_string())
+                .filter(|name| name != "broadcaster")
+                .sorted()
+                .collect();
+            (graph.name(input).to_string(), modules)
+        })
+        .sorted()
+        .collect())
+}
+
+/// The first button press at which all cycles fire together.
+///
+/// A cycle is given as `(first, period)` and fires at `first`, `first + period`, ...
+fn first_common_press(cycles: impl IntoIterator<Item = (u32, u32)>) -> Option<u64> {
+    let cycles = cycles.into_iter().collect_vec();
+    let (residue, modulus) = math::crt(cycles.iter().map(|(f, p)| (*f as i128, *p as i128)))?;
+    let earliest = cycles.iter().map(|(f, _)| *f as i128).max().unwrap_or(0);
+    // move the residue up to the first press where every cycle has fired at least once
+    let press = if residue < earliest {
+        residue + (earliest - residue + modulus - 1) / modulus * modulus
+    } else {
+        residue
+    };
+    u64::try_from(press).ok()
+}
+
+pub fn part_two(input: &str) -> Option<u64> {
+    let (modules, adjacencies) = parse(input).ok()?;
+    presses_until_low(&modules, &adjacencies, "rx").ok()
+}
 
-    Some(lcm(steps))
+/// `--export-graph`, `--vcd` and `--subcircuits` report on the circuit, once after both parts.
+fn extras(input: &str) {
+    let Ok((modules, adjacencies)) = parse(input) else {
+        return;
+    };
+    if let Some(format) = export_graph_option() {
+        print!("{}", circuit_graph(&modules, &adjacencies).export(format));
+    }
+    if let Some((file, filter)) = vcd_options() {
+        let mut trace = Trace::with_filter(filter);
+        press_button(&mut modules.clone(), &adjacencies, Some(&mut trace));
+        match std::fs::write(&file, trace.to_vcd(&modules)) {
+            Ok(_) => println!("Wrote trace to {file}"),
+            Err(e) => eprintln!("Failed to write trace to {file}: {e}"),
+        }
+    }
+    // `--subcircuits` lists the independent counters that have to line up
+    if std::env::args().any(|arg| arg == "--subcircuits") {
+        match subcircuits(&modules, &adjacencies, "rx") {
+            Ok(subcircuits) => {
+                for (input, modules) in subcircuits {
+                    println!(
+                        "{input} ({} modules): {}",
+                        modules.len(),
+                        modules.join(", ")
+                    );
+                }
+            }
+            Err(e) => eprintln!("No sub-circuits found: {e:?}"),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_first_common_press() {
+        // the usual case, every cycle starts with its period
+        assert_eq!(
+            first_common_press([(3739, 3739), (3761, 3761)]),
+            Some(3739 * 3761)
+        );
+        // cycles with offsets
+        assert_eq!(first_common_press([(3, 4), (5, 6)]), Some(11));
+        assert_eq!(first_common_press([(1, 2), (9, 4)]), Some(9));
+        // cycles that never line up
+        assert_eq!(first_common_press([(0, 4), (1, 6)]), None);
+    }
+
+    // two counters of different speed, `x` fires every 2nd press and `y` every 4th
+    const COUNTERS: &str = "broadcaster -> f1
+%f1 -> f2, x
+%f2 -> y
+&x -> hub
+&y -> hub
+&hub -> rx";
+
+    #[test]
+    fn test_find_cycles() {
+        let (mut modules, adjacencies) = parse(COUNTERS).unwrap();
+        assert_eq!(
+            find_cycles(&mut modules, &adjacencies, "hub"),
+            Ok(vec![("x".to_string(), 2, 2), ("y".to_string(), 4, 4)])
+        );
+        assert_eq!(
+            find_cycles(&mut modules, &adjacencies, "f1"),
+            Err(CircuitError::UnsupportedFeeder("f1".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_presses_until_low() {
+        let (modules, adjacencies) = parse(COUNTERS).unwrap();
+        assert_eq!(presses_until_low(&modules, &adjacencies, "rx"), Ok(4));
+        // not fed by a conjunction, but reached by simulating
+        assert_eq!(presses_until_low(&modules, &adjacencies, "f2"), Ok(2));
+        assert_eq!(
+            presses_until_low(&modules, &adjacencies, "nowhere"),
+            Err(CircuitError::Unreachable("nowhere".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_subcircuits() {
+        let (modules, adjacencies) = parse(COUNTERS).unwrap();
+        assert_eq!(
+            subcircuits(&modules, &adjacencies, "rx"),
+            Ok(vec![
+                ("x".to_string(), vec!["f1".to_string(), "x".to_string()]),
+                (
+                    "y".to_string(),
+                    vec!["f1".to_string(), "f2".to_string(), "y".to_string()]
+                ),
+            ])
+        );
+        assert_eq!(
+            subcircuits(&modules, &adjacencies, "f2"),
+            Err(CircuitError::UnsupportedFeeder("f1".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_circuit_graph() {
+        let (modules, adjacencies) = parse(COUNTERS).unwrap();
+        assert_eq!(
+            circuit_graph(&modules, &adjacencies).to_mermaid(),
+            r#"flowchart LR
+    broadcaster{{"broadcaster"}}
+    f1["%f1"]
+    f2["%f2"]
+    hub{"&hub"}
+    rx(("rx"))
+    x{"&x"}
+    y{"&y"}
+    broadcaster --> f1
+    f1 --> f2
+    f1 --> x
+    f2 --> y
+    hub --> rx
+    x --> hub
+    y --> hub
+"#
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        let (mut modules, adjacencies) = parse(COUNTERS).unwrap();
+        let initial = modules.clone();
+        let mut trace = Trace::with_filter(vec!["f1".to_string()]);
+        for press in 1..=2 {
+            simulate(&mut modules, &adjacencies, &mut |from, to, sig| {
+                trace.record(press, from, to, sig)
+            });
+        }
+
+        let events = trace
+            .events
+            .iter()
+            .map(|e| (e.press, e.time, e.from.as_str(), e.to.as_str(), e.signal))
+            .collect_vec();
+        assert_eq!(
+            events,
+            vec![
+                (1, 1, "broadcaster", "f1", false),
+                (1, 2, "f1", "f2", true),
+                (1, 3, "f1", "x", true),
+                (2, 7, "broadcaster", "f1", false),
+                (2, 8, "f1", "f2", false),
+                (2, 9, "f1", "x", false),
+            ]
+        );
+
+        assert_eq!(
+            trace.to_vcd(&initial),
+            "$timescale 1ns $end
+$scope module circuit $end
+$var integer 32 ! press $end
+$var wire 1 \" f1 $end
+$upscope $end
+$enddefinitions $end
+$dumpvars
+b0 !
+0\"
+$end
+#1
+b1 !
+#2
+1\"
+#7
+b10 !
+#8
+0\"
+#15
+"
+        );
+    }
+
+    #[test]
+    fn test_vcd_identifier() {
+        assert_eq!(vcd_identifier(0), "!");
+        assert_eq!(vcd_identifier(93), "~");
+        assert_eq!(vcd_identifier(94), "!!");
+        assert_eq!(vcd_identifier(95), "\"!");
+    }
+
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(11687500));
     }
 
     #[test]
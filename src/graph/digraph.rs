@@ -0,0 +1,344 @@
+use std::collections::{HashMap, VecDeque};
+
+/// A directed graph over named nodes.
+///
+/// Names are interned to `usize` ids in the order they are first seen, ids are stable for the
+/// lifetime of the graph and index into [`Digraph::name`]. Duplicate edges are ignored.
+///
+/// ```
+/// use advent_of_code::graph::Digraph;
+///
+/// let graph = Digraph::from_edges([("in", "px"), ("px", "qkq"), ("qkq", "px")]);
+/// let px = graph.id("px").unwrap();
+/// assert!(graph.topological_sort().is_err());
+/// assert_eq!(graph.names(&graph.find_cycle().unwrap()), ["px", "qkq"]);
+/// assert_eq!(graph.names(&graph.ancestors([px])), ["in", "px", "qkq"]);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Digraph {
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+    successors: Vec<Vec<usize>>,
+    predecessors: Vec<Vec<usize>>,
+}
+
+impl Digraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_edges<S: AsRef<str>>(edges: impl IntoIterator<Item = (S, S)>) -> Self {
+        let mut graph = Self::new();
+        for (from, to) in edges {
+            graph.add_edge(from.as_ref(), to.as_ref());
+        }
+        graph
+    }
+
+    /// Builds the graph from adjacency lists like `name -> [targets]`.
+    ///
+    /// The sources are interned sorted by name, so the ids do not depend on the iteration order
+    /// of e.g. a [`HashMap`].
+    pub fn from_adjacencies<K, V, T>(adjacencies: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: AsRef<str>,
+        V: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        let mut adjacencies = adjacencies.into_iter().collect::<Vec<_>>();
+        adjacencies.sort_by(|(a, _), (b, _)| a.as_ref().cmp(b.as_ref()));
+        let mut graph = Self::new();
+        for (from, _) in &adjacencies {
+            graph.add_node(from.as_ref());
+        }
+        for (from, targets) in adjacencies {
+            for to in targets {
+                graph.add_edge(from.as_ref(), to.as_ref());
+            }
+        }
+        graph
+    }
+
+    /// The id of `name`, adding it as a node without edges if it is new.
+    pub fn add_node(&mut self, name: &str) -> usize {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len();
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        self.successors.push(vec![]);
+        self.predecessors.push(vec![]);
+        id
+    }
+
+    pub fn add_edge(&mut self, from: &str, to: &str) {
+        let (from, to) = (self.add_node(from), self.add_node(to));
+        if !self.successors[from].contains(&to) {
+            self.successors[from].push(to);
+            self.predecessors[to].push(from);
+        }
+    }
+
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    /// # Panics
+    /// Panics if `id` is not a node of this graph.
+    pub fn name(&self, id: usize) -> &str {
+        &self.names[id]
+    }
+
+    /// Maps ids back to their names.
+    pub fn names(&self, ids: &[usize]) -> Vec<&str> {
+        ids.iter().map(|&id| self.name(id)).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn successors(&self, id: usize) -> &[usize] {
+        &self.successors[id]
+    }
+
+    pub fn predecessors(&self, id: usize) -> &[usize] {
+        &self.predecessors[id]
+    }
+
+    /// All nodes reachable from `start` including `start` itself, sorted by id.
+    pub fn descendants(&self, start: impl IntoIterator<Item = usize>) -> Vec<usize> {
+        self.search(start, &self.successors)
+    }
+
+    /// All nodes that can reach `target` including `target` itself, sorted by id.
+    pub fn ancestors(&self, target: impl IntoIterator<Item = usize>) -> Vec<usize> {
+        self.search(target, &self.predecessors)
+    }
+
+    fn search(&self, start: impl IntoIterator<Item = usize>, edges: &[Vec<usize>]) -> Vec<usize> {
+        let mut seen = vec![false; self.len()];
+        let mut queue = VecDeque::new();
+        for id in start {
+            if !seen[id] {
+                seen[id] = true;
+                queue.push_back(id);
+            }
+        }
+        while let Some(id) = queue.pop_front() {
+            for &next in &edges[id] {
+                if !seen[next] {
+                    seen[next] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+        (0..self.len()).filter(|&id| seen[id]).collect()
+    }
+
+    /// The nodes ordered so that every edge points forward, ties are broken by id.
+    ///
+    /// Returns a cycle as error if there is no such order.
+    pub fn topological_sort(&self) -> Result<Vec<usize>, Vec<usize>> {
+        let mut in_degree = self.predecessors.iter().map(Vec::len).collect::<Vec<_>>();
+        let mut ready = (0..self.len())
+            .filter(|&id| in_degree[id] == 0)
+            .collect::<VecDeque<_>>();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(id) = ready.pop_front() {
+            order.push(id);
+            for &next in &self.successors[id] {
+                in_degree[next] -= 1;
+                if in_degree[next] == 0 {
+                    ready.push_back(next);
+                }
+            }
+        }
+        if order.len() == self.len() {
+            Ok(order)
+        } else {
+            Err(self
+                .find_cycle()
+                .expect("nodes left over by the sort lie on a cycle"))
+        }
+    }
+
+    /// The strongly connected components by Tarjan's algorithm.
+    ///
+    /// Components come in reverse topological order, i.e. no edge leads from a component to
+    /// one listed after it. The ids in each component are sorted.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        const UNVISITED: usize = usize::MAX;
+        let mut index = vec![UNVISITED; self.len()];
+        let mut low_link = vec![0; self.len()];
+        let mut on_stack = vec![false; self.len()];
+        let mut stack = vec![];
+        let mut components = vec![];
+        let mut next_index = 0;
+
+        for root in 0..self.len() {
+            if index[root] != UNVISITED {
+                continue;
+            }
+            // iterative depth first search, every frame is a node and its next successor
+            let mut frames = vec![(root, 0)];
+            index[root] = next_index;
+            low_link[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some((id, edge)) = frames.last_mut() {
+                let id = *id;
+                if let Some(&next) = self.successors[id].get(*edge) {
+                    *edge += 1;
+                    if index[next] == UNVISITED {
+                        index[next] = next_index;
+                        low_link[next] = next_index;
+                        next_index += 1;
+                        stack.push(next);
+                        on_stack[next] = true;
+                        frames.push((next, 0));
+                    } else if on_stack[next] {
+                        low_link[id] = low_link[id].min(index[next]);
+                    }
+                    continue;
+                }
+
+                frames.pop();
+                if let Some(&(parent, _)) = frames.last() {
+                    low_link[parent] = low_link[parent].min(low_link[id]);
+                }
+                if low_link[id] == index[id] {
+                    let mut component = vec![];
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == id {
+                            break;
+                        }
+                    }
+                    component.sort_unstable();
+                    components.push(component);
+                }
+            }
+        }
+        components
+    }
+
+    /// The components that contain a cycle, i.e. more than one node or a node with a self loop.
+    pub fn cyclic_components(&self) -> Vec<Vec<usize>> {
+        self.strongly_connected_components()
+            .into_iter()
+            .filter(|c| c.len() > 1 || self.successors[c[0]].contains(&c[0]))
+            .collect()
+    }
+
+    /// Some cycle of the graph as its nodes in order, starting at the smallest id of its
+    /// component.
+    pub fn find_cycle(&self) -> Option<Vec<usize>> {
+        let component = self.cyclic_components().into_iter().next()?;
+        let start = component[0];
+        // shortest way back to `start` inside the component
+        let mut parent = HashMap::new();
+        let mut queue = VecDeque::from([start]);
+        while let Some(id) = queue.pop_front() {
+            for &next in &self.successors[id] {
+                if next == start {
+                    let mut cycle = vec![id];
+                    while let Some(&p) = parent.get(cycle.last().unwrap()) {
+                        cycle.push(p);
+                    }
+                    cycle.reverse();
+                    return Some(cycle);
+                }
+                if component.binary_search(&next).is_ok() && !parent.contains_key(&next) {
+                    parent.insert(next, id);
+                    queue.push_back(next);
+                }
+            }
+        }
+        unreachable!("every node of a cyclic component lies on a cycle")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph() -> Digraph {
+        // a -> b -> c -> a is a cycle, d hangs off it, e is on its own
+        let mut graph = Digraph::from_edges([("a", "b"), ("b", "c"), ("c", "a"), ("c", "d")]);
+        graph.add_node("e");
+        graph
+    }
+
+    #[test]
+    fn interning() {
+        let mut graph = graph();
+        assert_eq!(graph.len(), 5);
+        assert_eq!(graph.id("c"), Some(2));
+        assert_eq!(graph.name(2), "c");
+        assert_eq!(graph.id("x"), None);
+        assert_eq!(graph.add_node("a"), 0);
+        graph.add_edge("a", "b");
+        assert_eq!(graph.successors(0), [1]);
+        assert_eq!(graph.predecessors(0), [2]);
+    }
+
+    #[test]
+    fn from_adjacencies() {
+        let adjacencies = HashMap::from([
+            ("y".to_string(), vec!["z".to_string()]),
+            ("x".to_string(), vec!["y".to_string(), "z".to_string()]),
+        ]);
+        let graph = Digraph::from_adjacencies(&adjacencies);
+        assert_eq!(graph.names(&[0, 1, 2]), ["x", "y", "z"]);
+        assert_eq!(graph.successors(0), [1, 2]);
+    }
+
+    #[test]
+    fn reachability() {
+        let graph = graph();
+        assert_eq!(graph.names(&graph.descendants([3])), ["d"]);
+        assert_eq!(graph.names(&graph.descendants([1])), ["a", "b", "c", "d"]);
+        assert_eq!(graph.names(&graph.ancestors([3])), ["a", "b", "c", "d"]);
+        assert_eq!(graph.names(&graph.ancestors([4])), ["e"]);
+        assert!(graph.descendants([]).is_empty());
+    }
+
+    #[test]
+    fn topological_sort() {
+        let dag = Digraph::from_edges([("a", "c"), ("b", "c"), ("c", "d"), ("a", "d")]);
+        let order = dag.topological_sort().unwrap();
+        assert_eq!(dag.names(&order), ["a", "b", "c", "d"]);
+        assert_eq!(graph().topological_sort(), Err(vec![0, 1, 2]));
+    }
+
+    #[test]
+    fn strongly_connected_components() {
+        let graph = graph();
+        assert_eq!(
+            graph.strongly_connected_components(),
+            vec![vec![3], vec![0, 1, 2], vec![4]]
+        );
+        assert_eq!(graph.cyclic_components(), vec![vec![0, 1, 2]]);
+
+        let self_loop = Digraph::from_edges([("a", "a"), ("a", "b")]);
+        assert_eq!(self_loop.find_cycle(), Some(vec![0]));
+        assert_eq!(Digraph::from_edges([("a", "b")]).find_cycle(), None);
+    }
+
+    #[test]
+    fn long_chain() {
+        // deep enough to overflow the stack of a recursive search
+        let graph = Digraph::from_edges((0..100_000).map(|i| (i.to_string(), (i + 1).to_string())));
+        assert_eq!(graph.strongly_connected_components().len(), 100_001);
+        assert_eq!(graph.topological_sort().map(|it| it.len()), Ok(100_001));
+    }
+}
@@ -0,0 +1,261 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    error::Error,
+    fmt::Display,
+    str::FromStr,
+};
+
+/// How a node is drawn. Every format maps these to its closest equivalent.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NodeShape {
+    #[default]
+    Box,
+    Rounded,
+    Diamond,
+    Hexagon,
+    Circle,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Node {
+    label: String,
+    shape: NodeShape,
+}
+
+/// A directed graph to export for drawing, e.g. with `dot` or [mermaid.live](https://mermaid.live).
+///
+/// Nodes and edges are written sorted by name, so exporting the same graph always results in
+/// the same output, whatever order it was built in.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GraphExport {
+    name: String,
+    nodes: BTreeMap<String, Node>,
+    edges: BTreeSet<(String, String)>,
+}
+
+impl GraphExport {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Adds a node or replaces the label and shape of an existing one.
+    pub fn node(&mut self, id: &str, label: &str, shape: NodeShape) -> &mut Self {
+        self.nodes.insert(
+            id.to_string(),
+            Node {
+                label: label.to_string(),
+                shape,
+            },
+        );
+        self
+    }
+
+    /// Adds an edge, nodes that were not added before get their id as label and the default shape.
+    pub fn edge(&mut self, from: &str, to: &str) -> &mut Self {
+        for id in [from, to] {
+            if !self.nodes.contains_key(id) {
+                self.node(id, id, NodeShape::default());
+            }
+        }
+        self.edges.insert((from.to_string(), to.to_string()));
+        self
+    }
+
+    pub fn export(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Mermaid => self.to_mermaid(),
+            GraphFormat::Dot => self.to_dot(),
+            GraphFormat::GraphMl => self.to_graphml(),
+        }
+    }
+
+    pub fn to_mermaid(&self) -> String {
+        let mut result = String::from("flowchart LR\n");
+        for (id, node) in &self.nodes {
+            let label = node.label.replace('"', "#quot;");
+            let shape = match node.shape {
+                NodeShape::Box => format!("[\"{label}\"]"),
+                NodeShape::Rounded => format!("(\"{label}\")"),
+                NodeShape::Diamond => format!("{{\"{label}\"}}"),
+                NodeShape::Hexagon => format!("{{{{\"{label}\"}}}}"),
+                NodeShape::Circle => format!("((\"{label}\"))"),
+            };
+            result.push_str(&format!("    {id}{shape}\n"));
+        }
+        for (from, to) in &self.edges {
+            result.push_str(&format!("    {from} --> {to}\n"));
+        }
+        result
+    }
+
+    pub fn to_dot(&self) -> String {
+        let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+        let mut result = format!("digraph {} {{\n", quote(&self.name));
+        for (id, node) in &self.nodes {
+            let shape = match node.shape {
+                NodeShape::Box => "shape=box",
+                NodeShape::Rounded => "shape=box, style=rounded",
+                NodeShape::Diamond => "shape=diamond",
+                NodeShape::Hexagon => "shape=hexagon",
+                NodeShape::Circle => "shape=circle",
+            };
+            result.push_str(&format!(
+                "    {} [label={}, {shape}];\n",
+                quote(id),
+                quote(&node.label)
+            ));
+        }
+        for (from, to) in &self.edges {
+            result.push_str(&format!("    {} -> {};\n", quote(from), quote(to)));
+        }
+        result.push_str("}\n");
+        result
+    }
+
+    pub fn to_graphml(&self) -> String {
+        let escape = |s: &str| {
+            s.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+                .replace('"', "&quot;")
+        };
+        let mut result = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n",
+            "  <key id=\"shape\" for=\"node\" attr.name=\"shape\" attr.type=\"string\"/>\n",
+        ));
+        result.push_str(&format!(
+            "  <graph id=\"{}\" edgedefault=\"directed\">\n",
+            escape(&self.name)
+        ));
+        for (id, node) in &self.nodes {
+            let shape = match node.shape {
+                NodeShape::Box => "box",
+                NodeShape::Rounded => "rounded",
+                NodeShape::Diamond => "diamond",
+                NodeShape::Hexagon => "hexagon",
+                NodeShape::Circle => "circle",
+            };
+            result.push_str(&format!(
+                concat!(
+                    "    <node id=\"{}\">\n",
+                    "      <data key=\"label\">{}</data>\n",
+                    "      <data key=\"shape\">{}</data>\n",
+                    "    </node>\n"
+                ),
+                escape(id),
+                escape(&node.label),
+                shape
+            ));
+        }
+        for (from, to) in &self.edges {
+            result.push_str(&format!(
+                "    <edge source=\"{}\" target=\"{}\"/>\n",
+                escape(from),
+                escape(to)
+            ));
+        }
+        result.push_str("  </graph>\n</graphml>\n");
+        result
+    }
+}
+
+/// The supported export formats, parsed from `mermaid`, `dot` or `graphml`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Mermaid,
+    Dot,
+    GraphMl,
+}
+
+impl FromStr for GraphFormat {
+    type Err = GraphFormatFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "mermaid" => Ok(Self::Mermaid),
+            "dot" => Ok(Self::Dot),
+            "graphml" => Ok(Self::GraphMl),
+            _ => Err(GraphFormatFromStrError),
+        }
+    }
+}
+
+/// An error which can be returned when parsing a [`GraphFormat`].
+#[derive(Debug)]
+pub struct GraphFormatFromStrError;
+
+impl Error for GraphFormatFromStrError {}
+
+impl Display for GraphFormatFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting one of the graph formats mermaid, dot or graphml")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph() -> GraphExport {
+        let mut graph = GraphExport::new("test");
+        // added in reverse on purpose, the output is sorted anyway
+        graph
+            .edge("b", "c")
+            .edge("a", "b")
+            .node("a", "start", NodeShape::Hexagon)
+            .node("b", "&b", NodeShape::Diamond);
+        graph
+    }
+
+    #[test]
+    fn mermaid() {
+        assert_eq!(
+            graph().to_mermaid(),
+            r#"flowchart LR
+    a{{"start"}}
+    b{"&b"}
+    c["c"]
+    a --> b
+    b --> c
+"#
+        );
+    }
+
+    #[test]
+    fn dot() {
+        assert_eq!(
+            graph().to_dot(),
+            r#"digraph "test" {
+    "a" [label="start", shape=hexagon];
+    "b" [label="&b", shape=diamond];
+    "c" [label="c", shape=box];
+    "a" -> "b";
+    "b" -> "c";
+}
+"#
+        );
+    }
+
+    #[test]
+    fn graphml() {
+        let graphml = graph().to_graphml();
+        assert!(graphml.contains("<data key=\"label\">&amp;b</data>"));
+        assert!(graphml.contains("<edge source=\"a\" target=\"b\"/>"));
+        assert!(graphml.find("<node id=\"a\">") < graphml.find("<node id=\"b\">"));
+    }
+
+    #[test]
+    fn format_from_str() {
+        assert_eq!("dot".parse::<GraphFormat>().unwrap(), GraphFormat::Dot);
+        assert_eq!(
+            "GraphML".parse::<GraphFormat>().unwrap(),
+            GraphFormat::GraphMl
+        );
+        assert!("png".parse::<GraphFormat>().is_err());
+    }
+}
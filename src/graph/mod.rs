@@ -0,0 +1,7 @@
+//! Directed graphs, e.g. the workflows of day 19 or the module network of day 20.
+
+mod digraph;
+mod export;
+
+pub use digraph::*;
+pub use export::*;
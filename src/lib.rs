@@ -1 +1,8 @@
+pub mod graph;
+pub mod math;
+pub mod memo;
+pub mod parse;
+pub mod polygon;
+pub mod render;
+pub mod scanner;
 pub mod template;
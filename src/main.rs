@@ -21,6 +21,7 @@ mod args {
             release: bool,
             time: bool,
             submit: Option<u8>,
+            args: Vec<String>,
         },
         All {
             release: bool,
@@ -29,7 +30,18 @@ mod args {
     }
 
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
-        let mut args = pico_args::Arguments::from_env();
+        // everything after `--` is passed on to the solution unchanged
+        let mut raw_args = std::env::args_os().skip(1).collect::<Vec<_>>();
+        let forwarded = match raw_args.iter().position(|arg| arg == "--") {
+            Some(index) => raw_args
+                .split_off(index)
+                .into_iter()
+                .skip(1)
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect(),
+            None => vec![],
+        };
+        let mut args = pico_args::Arguments::from_vec(raw_args);
 
         let app_args = match args.subcommand()?.as_deref() {
             Some("all") => AppArguments::All {
@@ -51,6 +63,7 @@ mod args {
                 release: args.contains("--release"),
                 submit: args.opt_value_from_str("--submit")?,
                 time: args.contains("--time"),
+                args: forwarded,
             },
             Some(x) => {
                 eprintln!("Unknown command: {x}");
@@ -92,7 +105,8 @@ fn main() {
                 release,
                 time,
                 submit,
-            } => solve::handle(day, release, time, submit),
+                args,
+            } => solve::handle(day, release, time, submit, &args),
         },
     };
 }
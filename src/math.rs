@@ -0,0 +1,186 @@
+//! Number theory helpers for cycle and period puzzles.
+//!
+//! ```
+//! use advent_of_code::math;
+//!
+//! // cycles of length 4 and 6 that first fire at 3 and 5 line up every 12 steps, starting at 11
+//! assert_eq!(math::crt([(3, 4), (5, 6)]), Some((11, 12)));
+//! assert_eq!(math::lcm_all([4, 6, 10]), Some(60));
+//! ```
+
+/// The greatest common divisor, `gcd(0, 0)` is `0`.
+pub fn gcd(a: u64, b: u64) -> u64 {
+    let (mut a, mut b) = (a, b);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// The least common multiple, or [`None`] if it does not fit into a `u64`.
+///
+/// `lcm(0, n)` is `0`.
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// The least common multiple of all values, `1` for no values at all.
+/// Returns [`None`] if the result does not fit into a `u64`.
+pub fn lcm_all(values: impl IntoIterator<Item = u64>) -> Option<u64> {
+    values.into_iter().try_fold(1, lcm)
+}
+
+/// The extended euclidean algorithm.
+///
+/// Returns `(g, x, y)` with `g = gcd(a, b) >= 0` and `a * x + b * y = g`.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The inverse of `a` modulo `modulus`, if `a` and `modulus` are coprime.
+pub fn mod_inverse(a: i128, modulus: i128) -> Option<i128> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    (g == 1).then(|| x.rem_euclid(modulus))
+}
+
+/// `base^exp mod modulus` by repeated squaring.
+///
+/// # Panics
+/// Panics if `modulus` is `0`.
+pub fn mod_pow(base: u64, exp: u64, modulus: u64) -> u64 {
+    let modulus = modulus as u128;
+    let mut result = 1 % modulus;
+    let mut base = base as u128 % modulus;
+    let mut exp = exp;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exp >>= 1;
+    }
+    result as u64
+}
+
+/// Solves a system of congruences `x ≡ residue (mod modulus)` with the chinese remainder theorem.
+///
+/// The moduli do not need to be coprime. Returns the combined `(residue, modulus)` with
+/// `0 <= residue < modulus`, or [`None`] if the congruences contradict each other, a modulus is
+/// not positive or the combined modulus overflows. No congruences at all result in `(0, 1)`.
+pub fn crt(congruences: impl IntoIterator<Item = (i128, i128)>) -> Option<(i128, i128)> {
+    congruences
+        .into_iter()
+        .try_fold((0, 1), |(r1, m1), (r2, m2)| {
+            if m2 <= 0 {
+                return None;
+            }
+            let r2 = r2.rem_euclid(m2);
+            let (g, p, _) = extended_gcd(m1, m2);
+            if (r2 - r1) % g != 0 {
+                return None;
+            }
+            // x = r1 + m1 * k with m1 * k ≡ r2 - r1 (mod m2)
+            let m2_g = m2 / g;
+            let k = ((r2 - r1) / g % m2_g).checked_mul(p)?.rem_euclid(m2_g);
+            let modulus = m1.checked_mul(m2_g)?;
+            let residue = r1.checked_add(m1.checked_mul(k)?)?.rem_euclid(modulus);
+            Some((residue, modulus))
+        })
+}
+
+/// The integer square root, i.e. the largest `r` with `r * r <= n`.
+pub fn isqrt(n: u64) -> u64 {
+    if n < 2 {
+        return n;
+    }
+    // newton's method, starting above the root and converging from there
+    let mut x = 1u64 << ((64 - n.leading_zeros()).div_ceil(2));
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 7), 7);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(0, 6), Some(0));
+        assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+        assert_eq!(lcm_all([2, 3, 4, 5]), Some(60));
+        assert_eq!(lcm_all([]), Some(1));
+    }
+
+    #[test]
+    fn extended_euclid() {
+        let (g, x, y) = extended_gcd(240, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+
+        let (g, x, y) = extended_gcd(-4, 6);
+        assert_eq!(g, 2);
+        assert_eq!(-4 * x + 6 * y, 2);
+    }
+
+    #[test]
+    fn modular_arithmetic() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(4, 8), None);
+        assert_eq!(mod_pow(4, 13, 497), 445);
+        assert_eq!(mod_pow(2, 0, 1), 0);
+        assert_eq!(mod_pow(u64::MAX, 2, u64::MAX - 1), 1);
+    }
+
+    #[test]
+    fn chinese_remainder() {
+        // coprime
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // not coprime, but consistent
+        assert_eq!(crt([(3, 4), (5, 6)]), Some((11, 12)));
+        // not coprime and contradicting
+        assert_eq!(crt([(0, 4), (1, 6)]), None);
+        // zero offsets just give the lcm
+        assert_eq!(crt([(0, 3739), (0, 3761)]), Some((0, 3739 * 3761)));
+        assert_eq!(crt([]), Some((0, 1)));
+        assert_eq!(crt([(1, 0)]), None);
+    }
+
+    #[test]
+    fn integer_sqrt() {
+        assert_eq!(isqrt(0), 0);
+        assert_eq!(isqrt(1), 1);
+        assert_eq!(isqrt(15), 3);
+        assert_eq!(isqrt(16), 4);
+        assert_eq!(isqrt(u64::MAX), u32::MAX as u64);
+        for n in 0..10_000u64 {
+            let r = isqrt(n);
+            assert!(r * r <= n && (r + 1) * (r + 1) > n);
+        }
+    }
+}
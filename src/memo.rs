@@ -0,0 +1,226 @@
+//! Memoization for recursive counting solutions.
+//!
+//! [`Memo`] is a cache that recursive functions pass along instead of a bare `HashMap`.
+//! [`fix`] wraps a recursive closure so that it does not need to pass anything along at all.
+//!
+//! ```
+//! use advent_of_code::memo::fix;
+//!
+//! let mut fib = fix(|fib, n: u64| if n < 2 { n } else { fib(n - 1) + fib(n - 2) });
+//! assert_eq!(fib.call(90), 2880067194370816120);
+//! assert_eq!(fib.stats().misses, 91);
+//! ```
+
+use std::{collections::HashMap, fmt::Display, hash::Hash};
+
+/// Hit and miss counters of a [`Memo`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MemoStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl MemoStats {
+    /// The share of lookups answered from the cache, `0.0` if there were no lookups yet.
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+impl Display for MemoStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} hits, {} misses ({:.1}% hit rate)",
+            self.hits,
+            self.misses,
+            self.hit_rate() * 100.0
+        )
+    }
+}
+
+/// A cache of already computed results of a function.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    stats: MemoStats,
+}
+
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self {
+            cache: HashMap::new(),
+            stats: MemoStats::default(),
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the cached value for `key` or computes and caches it with `compute`.
+    ///
+    /// `compute` gets the memo passed back in, so it can recurse through it. Whatever path
+    /// `compute` returns on, the result is cached.
+    pub fn get_or_compute(&mut self, key: K, compute: impl FnOnce(&mut Self, &K) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            self.stats.hits += 1;
+            return value.clone();
+        }
+        self.stats.misses += 1;
+        let value = compute(self, &key);
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    /// Forgets all cached values, e.g. between two input lines. The statistics are kept.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+
+    /// The number of cached values.
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = MemoStats::default();
+    }
+}
+
+/// A recursive function with a built-in [`Memo`], see [`fix`].
+pub struct Memoized<K, V, F> {
+    memo: Memo<K, V>,
+    function: F,
+}
+
+impl<K, V, F> Memoized<K, V, F>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    /// Calls the function, reusing every result computed so far.
+    pub fn call(&mut self, key: K) -> V {
+        fn go<K, V, F>(memo: &mut Memo<K, V>, function: &F, key: K) -> V
+        where
+            K: Eq + Hash + Clone,
+            V: Clone,
+            F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+        {
+            memo.get_or_compute(key, |memo, key| {
+                function(&mut |next| go(memo, function, next), key.clone())
+            })
+        }
+
+        go(&mut self.memo, &self.function, key)
+    }
+
+    /// Forgets all cached values. The statistics are kept.
+    pub fn clear(&mut self) {
+        self.memo.clear();
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.memo.stats()
+    }
+
+    pub fn memo(&self) -> &Memo<K, V> {
+        &self.memo
+    }
+}
+
+/// Turns a recursive closure into a memoized function.
+///
+/// The closure gets itself as first argument and uses it for all recursive calls, those calls
+/// go through the cache.
+pub fn fix<K, V, F>(function: F) -> Memoized<K, V, F>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    Memoized {
+        memo: Memo::new(),
+        function,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fib(n: u64, memo: &mut Memo<u64, u64>) -> u64 {
+        memo.get_or_compute(n, |memo, n| {
+            if *n < 2 {
+                return *n;
+            }
+            fib(n - 1, memo) + fib(n - 2, memo)
+        })
+    }
+
+    #[test]
+    fn memo_caches_every_path() {
+        let mut memo = Memo::new();
+        assert_eq!(fib(50, &mut memo), 12586269025);
+        assert_eq!(memo.len(), 51);
+        assert_eq!(
+            memo.stats(),
+            MemoStats {
+                hits: 48,
+                misses: 51
+            }
+        );
+
+        // second call is a single hit
+        fib(50, &mut memo);
+        assert_eq!(memo.stats().hits, 49);
+    }
+
+    #[test]
+    fn clear_keeps_stats() {
+        let mut memo = Memo::new();
+        fib(10, &mut memo);
+        memo.clear();
+        assert!(memo.is_empty());
+        assert_eq!(memo.stats().misses, 11);
+
+        memo.reset_stats();
+        assert_eq!(memo.stats(), MemoStats::default());
+    }
+
+    #[test]
+    fn fix_recursion() {
+        let mut fib = fix(|fib, n: u64| if n < 2 { n } else { fib(n - 1) + fib(n - 2) });
+        assert_eq!(fib.call(50), 12586269025);
+        assert_eq!(fib.stats().misses, 51);
+        assert_eq!(fib.memo().len(), 51);
+
+        fib.clear();
+        assert_eq!(fib.call(1), 1);
+        assert_eq!(fib.stats().misses, 52);
+    }
+
+    #[test]
+    fn hit_rate() {
+        assert_eq!(MemoStats::default().hit_rate(), 0.0);
+        let stats = MemoStats { hits: 3, misses: 1 };
+        assert_eq!(stats.hit_rate(), 0.75);
+        assert_eq!(stats.to_string(), "3 hits, 1 misses (75.0% hit rate)");
+    }
+}
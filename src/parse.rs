@@ -0,0 +1,392 @@
+//! Helpers for the recurring shapes of puzzle input.
+//!
+//! Every helper returns a [`ParseError`] with the line and column of the offending text instead
+//! of panicking. Positions are relative to the text the helper was given, [`parse_lines`] and
+//! [`Section::parse_lines`] shift them to the position in the whole input.
+//!
+//! ```
+//! use advent_of_code::{parse, pattern};
+//!
+//! let [x, m, a, s] = pattern!(r"x=(\d+),m=(\d+),a=(\d+),s=(\d+)")
+//!     .parse::<u32, 4>("{x=787,m=2655,a=1222,s=2876}")
+//!     .unwrap();
+//! assert_eq!((x, m, a, s), (787, 2655, 1222, 2876));
+//!
+//! assert_eq!(parse::integers::<i64>("seeds: 79 -14").unwrap(), vec![79, -14]);
+//! ```
+
+use std::{error::Error, fmt::Display, str::FromStr};
+
+use regex::Regex;
+
+/// An error which can be returned by the parsing helpers, positioned by line and column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line of the offending text.
+    pub line: usize,
+    /// 1-based column (in bytes) of the offending text.
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    /// Creates an error positioned at `part`, which has to be a slice of `text`.
+    /// Falls back to the start of `text` if it is not.
+    pub fn at(text: &str, part: &str, message: impl Into<String>) -> Self {
+        let offset = offset_of(text, part).unwrap_or(0);
+        let before = &text[..offset];
+        let line = before.matches('\n').count() + 1;
+        let column = offset - before.rfind('\n').map(|it| it + 1).unwrap_or(0) + 1;
+        Self::new(line, column, message)
+    }
+
+    /// Moves the error down by `lines`, e.g. from the position within a line to the position
+    /// within the whole input.
+    pub fn shifted(mut self, lines: usize) -> Self {
+        self.line += lines;
+        self
+    }
+}
+
+impl Error for ParseError {}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+/// The byte offset of `part` within `text`, if `part` is a slice of `text`.
+fn offset_of(text: &str, part: &str) -> Option<usize> {
+    let start = text.as_ptr() as usize;
+    let part_start = part.as_ptr() as usize;
+    (part_start >= start && part_start + part.len() <= start + text.len())
+        .then_some(part_start - start)
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A block of lines, separated from other blocks by blank lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Section<'a> {
+    /// The text of the section without surrounding blank lines.
+    pub text: &'a str,
+    /// The number of lines in the input before this section.
+    pub line_offset: usize,
+}
+
+impl<'a> Section<'a> {
+    pub fn lines(&self) -> std::str::Lines<'a> {
+        self.text.lines()
+    }
+
+    /// Parses every line of the section, see [`parse_lines`].
+    pub fn parse_lines<T>(
+        &self,
+        parse: impl FnMut(&'a str) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        parse_lines(self.text, parse).map_err(|e| e.shifted(self.line_offset))
+    }
+}
+
+/// Splits the input into blocks separated by one or more blank lines.
+pub fn sections(input: &str) -> Vec<Section<'_>> {
+    let mut sections = Vec::new();
+    let mut start = None;
+    let mut end = 0;
+    for (line_no, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            if let Some((first, offset)) = start.take() {
+                sections.push(section(input, first, end, offset));
+            }
+        } else {
+            let line_start = offset_of(input, line).unwrap_or(0);
+            start.get_or_insert((line_start, line_no));
+            end = line_start + line.len();
+        }
+    }
+    if let Some((first, offset)) = start {
+        sections.push(section(input, first, end, offset));
+    }
+    sections
+}
+
+fn section(input: &str, start: usize, end: usize, line_offset: usize) -> Section<'_> {
+    Section {
+        text: &input[start..end],
+        line_offset,
+    }
+}
+
+/// Expects exactly `N` sections, see [`sections`].
+pub fn sections_n<const N: usize>(input: &str) -> Result<[Section<'_>; N], ParseError> {
+    let sections = sections(input);
+    let found = sections.len();
+    sections.try_into().map_err(|_| {
+        ParseError::new(
+            input.lines().count().max(1),
+            1,
+            format!("expected {N} sections separated by blank lines, found {found}"),
+        )
+    })
+}
+
+/// Parses every line of `input` with `parse`, errors are moved to the line they occurred in.
+pub fn parse_lines<'a, T>(
+    input: &'a str,
+    mut parse: impl FnMut(&'a str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(line_no, line)| parse(line).map_err(|e| e.shifted(line_no)))
+        .collect()
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Parses `part` of `text` into a `T`, positioning the error at `part`.
+pub fn value<T: FromStr>(text: &str, part: &str) -> Result<T, ParseError> {
+    part.trim().parse::<T>().map_err(|_| {
+        ParseError::at(
+            text,
+            part,
+            format!(
+                "could not parse `{}` as {}",
+                part.trim(),
+                std::any::type_name::<T>()
+            ),
+        )
+    })
+}
+
+/// Finds all integers in `text`, e.g. `[79, 14]` in `seeds: 79 14`.
+///
+/// A `-` counts as sign if it is directly followed by a digit and not preceded by a letter or
+/// digit, so `seed-to-soil` contains no integers, but `x=-5` does.
+pub fn integers<T: FromStr>(text: &str) -> Result<Vec<T>, ParseError> {
+    let bytes = text.as_bytes();
+    let mut result = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let is_sign = bytes[i] == b'-'
+            && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)
+            && (i == 0 || !bytes[i - 1].is_ascii_alphanumeric());
+        if is_sign || bytes[i].is_ascii_digit() {
+            let start = i;
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            result.push(value(text, &text[start..i])?);
+        } else {
+            i += 1;
+        }
+    }
+    Ok(result)
+}
+
+/// Like [`str::split_once`], but fails with a positioned error if `delimiter` is missing.
+pub fn split_once<'a>(text: &'a str, delimiter: &str) -> Result<(&'a str, &'a str), ParseError> {
+    text.split_once(delimiter)
+        .ok_or_else(|| ParseError::at(text, text, format!("expected `{delimiter}` in `{text}`")))
+}
+
+/// Splits a list like `x=787,m=2655` or `a: 1, b: 2` into trimmed key value pairs.
+pub fn key_values<'a>(
+    text: &'a str,
+    item_separator: &str,
+    key_value_separator: &str,
+) -> Result<Vec<(&'a str, &'a str)>, ParseError> {
+    text.split(item_separator)
+        .filter(|item| !item.trim().is_empty())
+        .map(|item| {
+            item.split_once(key_value_separator)
+                .map(|(k, v)| (k.trim(), v.trim()))
+                .ok_or_else(|| {
+                    ParseError::at(
+                        text,
+                        item,
+                        format!("expected `key{key_value_separator}value`, found `{item}`"),
+                    )
+                })
+        })
+        .collect()
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A regular expression whose capture groups map to the fields of a line.
+///
+/// Use the [`pattern!`](crate::pattern) macro to compile it only once.
+#[derive(Debug)]
+pub struct Pattern {
+    regex: Regex,
+}
+
+impl Pattern {
+    /// # Panics
+    /// Panics if `regex` is not a valid regular expression.
+    pub fn new(regex: &str) -> Self {
+        Self {
+            regex: Regex::new(regex).expect("invalid pattern"),
+        }
+    }
+
+    /// Returns the text of the first `N` capture groups of the first match.
+    pub fn captures<'a, const N: usize>(&self, text: &'a str) -> Result<[&'a str; N], ParseError> {
+        let captures = self.regex.captures(text).ok_or_else(|| {
+            ParseError::at(
+                text,
+                text,
+                format!("`{text}` does not match `{}`", self.regex),
+            )
+        })?;
+
+        let mut result = [""; N];
+        for (i, r) in result.iter_mut().enumerate() {
+            *r = captures.get(i + 1).map(|m| m.as_str()).ok_or_else(|| {
+                ParseError::at(
+                    text,
+                    text,
+                    format!("group {} of `{}` did not match", i + 1, self.regex),
+                )
+            })?;
+        }
+        Ok(result)
+    }
+
+    /// Parses the first `N` capture groups into `T`s.
+    pub fn parse<T: FromStr, const N: usize>(&self, text: &str) -> Result<[T; N], ParseError> {
+        let captures = self.captures::<N>(text)?;
+        let values = captures
+            .iter()
+            .map(|c| value::<T>(text, c))
+            .collect::<Result<Vec<_>, _>>()?;
+        match values.try_into() {
+            Ok(values) => Ok(values),
+            Err(_) => unreachable!("there is one value per capture"),
+        }
+    }
+}
+
+/// Compiles a [`Pattern`](crate::parse::Pattern) once and returns a `&'static` reference to it.
+#[macro_export]
+macro_rules! pattern {
+    ($regex:literal) => {{
+        static PATTERN: std::sync::OnceLock<$crate::parse::Pattern> = std::sync::OnceLock::new();
+        PATTERN.get_or_init(|| $crate::parse::Pattern::new($regex))
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_position() {
+        let text = "abc\ndef\nghi";
+        let error = ParseError::at(text, &text[5..7], "oops");
+        assert_eq!((error.line, error.column), (2, 2));
+        assert_eq!(error.to_string(), "2:2: oops");
+        assert_eq!(error.shifted(3).line, 5);
+    }
+
+    #[test]
+    fn split_sections() {
+        let input = "a\nb\n\n\nc\n\nd\ne\n";
+        let sections = sections(input);
+        assert_eq!(
+            sections,
+            vec![
+                Section {
+                    text: "a\nb",
+                    line_offset: 0
+                },
+                Section {
+                    text: "c",
+                    line_offset: 4
+                },
+                Section {
+                    text: "d\ne",
+                    line_offset: 6
+                },
+            ]
+        );
+
+        assert!(sections_n::<3>(input).is_ok());
+        assert_eq!(
+            sections_n::<2>(input).unwrap_err().message,
+            "expected 2 sections separated by blank lines, found 3"
+        );
+    }
+
+    #[test]
+    fn section_errors_are_positioned_in_input() {
+        let input = "1\n\n2\nx3\n4";
+        let [_, second] = sections_n(input).unwrap();
+        let error = second.parse_lines(|l| value::<u32>(l, l)).unwrap_err();
+        assert_eq!((error.line, error.column), (4, 1));
+    }
+
+    #[test]
+    fn find_integers() {
+        assert_eq!(
+            integers::<u32>("seeds: 79 14 55 13").unwrap(),
+            vec![79, 14, 55, 13]
+        );
+        assert_eq!(integers::<i32>("x=-5, y=3-4").unwrap(), vec![-5, 3, 4]);
+        assert_eq!(
+            integers::<u32>("seed-to-soil map:").unwrap(),
+            Vec::<u32>::new()
+        );
+
+        let error = integers::<u8>("1 2 300").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 5));
+    }
+
+    #[test]
+    fn split() {
+        assert_eq!(split_once("a -> b", " -> ").unwrap(), ("a", "b"));
+        assert_eq!(
+            split_once("a b", " -> ").unwrap_err().message,
+            "expected ` -> ` in `a b`"
+        );
+    }
+
+    #[test]
+    fn key_value_list() {
+        assert_eq!(
+            key_values("x=787,m=2655", ",", "=").unwrap(),
+            vec![("x", "787"), ("m", "2655")]
+        );
+        assert_eq!(
+            key_values("a: 1, b: 2", ",", ":").unwrap(),
+            vec![("a", "1"), ("b", "2")]
+        );
+        let error = key_values("a=1,b", ",", "=").unwrap_err();
+        assert_eq!(error.column, 5);
+    }
+
+    #[test]
+    fn patterns() {
+        let pattern = pattern!(r"(\w+)([<>])(\d+):(\w+)");
+        assert_eq!(
+            pattern.captures::<4>("a<2006:qkq").unwrap(),
+            ["a", "<", "2006", "qkq"]
+        );
+        assert!(pattern.captures::<4>("rfg").is_err());
+        assert!(pattern.captures::<5>("a<2006:qkq").is_err());
+
+        let [x, y] = pattern!(r"(\d+),(\d+)").parse::<u32, 2>("1,2").unwrap();
+        assert_eq!((x, y), (1, 2));
+    }
+}
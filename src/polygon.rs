@@ -0,0 +1,232 @@
+//! Exact area and lattice point counts of simple polygons with integer vertices.
+//!
+//! All calculations use `i128` and checked arithmetic, so huge dig plans (day 18) or long pipe
+//! loops (day 10) never silently overflow.
+
+use crate::math;
+
+/// A point on the integer lattice as `(x, y)`.
+pub type Point = (i128, i128);
+
+/// A simple (non self-intersecting) polygon on the integer lattice.
+///
+/// The vertices are stored in walking order, the closing edge from the last back to the first
+/// vertex is implicit. The orientation (clockwise or counter-clockwise) does not matter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    vertices: Vec<Point>,
+}
+
+impl Polygon {
+    /// Creates a polygon from its vertices in walking order.
+    pub fn new(vertices: Vec<Point>) -> Self {
+        Self { vertices }
+    }
+
+    /// Creates a polygon by walking from `start` along the given `(direction, length)` steps.
+    ///
+    /// The direction is a unit vector like `(1, 0)`, but any vector is scaled by `length`.
+    /// If the walk returns to `start`, the closing vertex is not duplicated.
+    ///
+    /// # Panics
+    /// Panics if a coordinate overflows `i128`.
+    pub fn from_steps(start: Point, steps: impl IntoIterator<Item = (Point, i128)>) -> Self {
+        let mut vertices = vec![start];
+        let mut pos = start;
+        for ((dx, dy), length) in steps {
+            pos = (
+                checked(pos.0.checked_add(checked(dx.checked_mul(length)))),
+                checked(pos.1.checked_add(checked(dy.checked_mul(length)))),
+            );
+            vertices.push(pos);
+        }
+        if vertices.len() > 1 && vertices.last() == Some(&start) {
+            vertices.pop();
+        }
+        Self { vertices }
+    }
+
+    /// The vertices in walking order.
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (Point, Point)> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |i| (self.vertices[i], self.vertices[(i + 1) % n]))
+    }
+
+    /// Twice the enclosed area, calculated with the shoelace formula.
+    ///
+    /// The area of a lattice polygon is always a multiple of `1/2`, doubling it keeps it exact.
+    ///
+    /// # Panics
+    /// Panics if the calculation overflows `i128`.
+    pub fn double_area(&self) -> i128 {
+        self.edges()
+            .map(|((x1, y1), (x2, y2))| {
+                checked(checked(x1.checked_mul(y2)).checked_sub(checked(x2.checked_mul(y1))))
+            })
+            .fold(0i128, |sum, it| checked(sum.checked_add(it)))
+            .abs()
+    }
+
+    /// The enclosed area, rounded down if it is not a whole number.
+    pub fn area(&self) -> i128 {
+        self.double_area() / 2
+    }
+
+    /// The number of lattice points on the boundary of the polygon.
+    ///
+    /// # Panics
+    /// Panics if the calculation overflows `i128`.
+    pub fn boundary_points(&self) -> i128 {
+        self.edges()
+            .map(|((x1, y1), (x2, y2))| {
+                let (g, _, _) =
+                    math::extended_gcd(checked(x2.checked_sub(x1)), checked(y2.checked_sub(y1)));
+                g
+            })
+            .fold(0i128, |sum, it| checked(sum.checked_add(it)))
+    }
+
+    /// The number of lattice points strictly inside the polygon, using Pick's theorem
+    /// (`A = I + B/2 - 1`).
+    pub fn interior_points(&self) -> i128 {
+        if self.vertices.len() < 3 {
+            return 0;
+        }
+        (self.double_area() - self.boundary_points() + 2) / 2
+    }
+
+    /// Whether `point` lies strictly inside the polygon, points on the boundary are not.
+    ///
+    /// Counts the edges crossed by a ray from `point` to the right, so this is O(n) per point.
+    ///
+    /// # Panics
+    /// Panics if the calculation overflows `i128`.
+    pub fn encloses(&self, (x, y): Point) -> bool {
+        let mut inside = false;
+        for ((x1, y1), (x2, y2)) in self.edges() {
+            // the cross product of the edge and the way to the point, zero if they are collinear
+            let (dx, dy) = (checked(x2.checked_sub(x1)), checked(y2.checked_sub(y1)));
+            let (px, py) = (checked(x.checked_sub(x1)), checked(y.checked_sub(y1)));
+            let cross =
+                checked(checked(dx.checked_mul(py)).checked_sub(checked(px.checked_mul(dy))));
+            if cross == 0
+                && x1.min(x2) <= x
+                && x <= x1.max(x2)
+                && y1.min(y2) <= y
+                && y <= y1.max(y2)
+            {
+                return false;
+            }
+            // half open in `y`, so a ray through a vertex counts only one of its edges
+            if (y1 > y) != (y2 > y) && (cross > 0) == (y2 > y1) {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+
+    /// The number of lattice points inside or on the boundary, e.g. the number of tiles of a
+    /// trench plus its dug out interior.
+    pub fn covered_points(&self) -> i128 {
+        match self.vertices.len() {
+            0 => 0,
+            1 => 1,
+            // a single line, walked there and back again
+            2 => self.boundary_points() / 2 + 1,
+            _ => self.interior_points() + self.boundary_points(),
+        }
+    }
+}
+
+fn checked(value: Option<i128>) -> i128 {
+    value.expect("polygon calculation overflows i128")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn square() {
+        let polygon = Polygon::new(vec![(0, 0), (0, 2), (2, 2), (2, 0)]);
+        assert_eq!(polygon.double_area(), 8);
+        assert_eq!(polygon.area(), 4);
+        assert_eq!(polygon.boundary_points(), 8);
+        assert_eq!(polygon.interior_points(), 1);
+        assert_eq!(polygon.covered_points(), 9);
+    }
+
+    #[test]
+    fn orientation_does_not_matter() {
+        let cw = Polygon::new(vec![(0, 0), (0, 3), (5, 3), (5, 0)]);
+        let ccw = Polygon::new(vec![(0, 0), (5, 0), (5, 3), (0, 3)]);
+        assert_eq!(cw.double_area(), ccw.double_area());
+        assert_eq!(cw.interior_points(), ccw.interior_points());
+    }
+
+    #[test]
+    fn general_lattice_polygon() {
+        // triangle with a diagonal edge, the diagonal only hits (0,0), (2,1) and (4,2)
+        let polygon = Polygon::new(vec![(0, 0), (4, 0), (4, 2)]);
+        assert_eq!(polygon.double_area(), 8);
+        assert_eq!(polygon.boundary_points(), 8);
+        assert_eq!(polygon.interior_points(), 1);
+    }
+
+    #[test]
+    fn from_steps() {
+        let polygon = Polygon::from_steps(
+            (0, 0),
+            [((1, 0), 3), ((0, 1), 2), ((-1, 0), 3), ((0, -1), 2)],
+        );
+        assert_eq!(polygon.vertices(), &[(0, 0), (3, 0), (3, 2), (0, 2)]);
+        assert_eq!(polygon.covered_points(), 12);
+    }
+
+    #[test]
+    fn huge_coordinates() {
+        let n = 1i128 << 60;
+        let polygon = Polygon::new(vec![(0, 0), (n, 0), (n, n), (0, n)]);
+        assert_eq!(polygon.area(), n * n);
+        assert_eq!(polygon.covered_points(), (n + 1) * (n + 1));
+    }
+
+    #[test]
+    fn encloses() {
+        // a U shape, the notch between x = 2 and x = 4 is outside
+        let polygon = Polygon::new(vec![
+            (0, 0),
+            (6, 0),
+            (6, 4),
+            (4, 4),
+            (4, 2),
+            (2, 2),
+            (2, 4),
+            (0, 4),
+        ]);
+        let inside = (-1..8)
+            .flat_map(|y| (-1..8).map(move |x| (x, y)))
+            .filter(|&p| polygon.encloses(p))
+            .count();
+        assert_eq!(inside, 9);
+        assert_eq!(inside as i128, polygon.interior_points());
+        // the ray to the right passes through the vertices of the notch
+        assert!(polygon.encloses((1, 2)));
+        assert!(!polygon.encloses((3, 2)));
+        assert!(!polygon.encloses((3, 3)));
+        assert!(!polygon.encloses((6, 1)));
+        assert!(polygon.encloses((5, 3)));
+        assert!(Polygon::new(vec![(0, 0), (4, 0), (4, 2)]).encloses((3, 1)));
+    }
+
+    #[test]
+    fn degenerate() {
+        assert_eq!(Polygon::new(vec![(0, 0)]).covered_points(), 1);
+        assert_eq!(Polygon::new(vec![]).covered_points(), 0);
+        assert_eq!(Polygon::new(vec![(0, 0), (0, 5)]).covered_points(), 6);
+    }
+}
@@ -0,0 +1,165 @@
+//! Minimal SVG output for drawing grids and paths without any image crates.
+//!
+//! ```
+//! use advent_of_code::render::{heat_color, Svg};
+//!
+//! let mut svg = Svg::new(20, 10);
+//! svg.rect(0, 0, 10, 10, &heat_color(1, 9));
+//! svg.polyline(&[(5, 5), (15, 5)], "black", 2);
+//! assert!(svg.to_string().starts_with("<svg"));
+//! ```
+
+use std::fmt::Display;
+
+/// An SVG image, built from shapes in drawing order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Svg {
+    width: usize,
+    height: usize,
+    elements: Vec<String>,
+}
+
+impl Svg {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            elements: vec![],
+        }
+    }
+
+    pub fn rect(
+        &mut self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+        fill: &str,
+    ) -> &mut Self {
+        self.elements.push(format!(
+            r#"<rect x="{x}" y="{y}" width="{width}" height="{height}" fill="{}"/>"#,
+            escape(fill)
+        ));
+        self
+    }
+
+    /// Text centered on `(x, y)`.
+    pub fn text(&mut self, x: usize, y: usize, size: usize, fill: &str, text: &str) -> &mut Self {
+        self.elements.push(format!(
+            r#"<text x="{x}" y="{y}" font-size="{size}" fill="{}" text-anchor="middle" dominant-baseline="central" font-family="monospace">{}</text>"#,
+            escape(fill),
+            escape(text)
+        ));
+        self
+    }
+
+    /// An open line through all points, with round joins so turns stay visible.
+    pub fn polyline(&mut self, points: &[(usize, usize)], stroke: &str, width: usize) -> &mut Self {
+        let points = points
+            .iter()
+            .map(|(x, y)| format!("{x},{y}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.elements.push(format!(
+            r#"<polyline points="{points}" fill="none" stroke="{}" stroke-width="{width}" stroke-linejoin="round" stroke-linecap="round"/>"#,
+            escape(stroke)
+        ));
+        self
+    }
+
+    pub fn circle(&mut self, x: usize, y: usize, radius: usize, fill: &str) -> &mut Self {
+        self.elements.push(format!(
+            r#"<circle cx="{x}" cy="{y}" r="{radius}" fill="{}"/>"#,
+            escape(fill)
+        ));
+        self
+    }
+
+    /// Writes the image to `file` with the part number added to the name, e.g. `route-1.svg`
+    /// for `route.svg`, and returns the name written to.
+    pub fn write_for_part(&self, file: &str, part: u8) -> std::io::Result<String> {
+        let file = part_file(file, part);
+        std::fs::write(&file, self.to_string())
+            .map_err(|e| std::io::Error::new(e.kind(), format!("{file}: {e}")))?;
+        Ok(file)
+    }
+}
+
+fn part_file(file: &str, part: u8) -> String {
+    match file.rsplit_once('.') {
+        Some((name, extension)) => format!("{name}-{part}.{extension}"),
+        None => format!("{file}-{part}"),
+    }
+}
+
+impl Display for Svg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+            self.width, self.height
+        )?;
+        for element in &self.elements {
+            writeln!(f, "  {element}")?;
+        }
+        writeln!(f, "</svg>")
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A color from dark blue for `0` over green and yellow to red for `max`.
+pub fn heat_color(value: u32, max: u32) -> String {
+    let ratio = if max == 0 {
+        0.0
+    } else {
+        value.min(max) as f64 / max as f64
+    };
+    let hue = (240.0 * (1.0 - ratio)).round();
+    let lightness = (25.0 + 25.0 * ratio).round();
+    format!("hsl({hue}, 80%, {lightness}%)")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn svg() {
+        let mut svg = Svg::new(20, 10);
+        svg.rect(0, 0, 10, 10, "red")
+            .text(5, 5, 8, "white", "<1>")
+            .polyline(&[(5, 5), (15, 5)], "black", 2)
+            .circle(15, 5, 3, "blue");
+        assert_eq!(
+            svg.to_string(),
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="10" viewBox="0 0 20 10">
+  <rect x="0" y="0" width="10" height="10" fill="red"/>
+  <text x="5" y="5" font-size="8" fill="white" text-anchor="middle" dominant-baseline="central" font-family="monospace">&lt;1&gt;</text>
+  <polyline points="5,5 15,5" fill="none" stroke="black" stroke-width="2" stroke-linejoin="round" stroke-linecap="round"/>
+  <circle cx="15" cy="5" r="3" fill="blue"/>
+</svg>
+"#
+        );
+    }
+
+    #[test]
+    fn part_files() {
+        assert_eq!(part_file("route.svg", 1), "route-1.svg");
+        assert_eq!(part_file("out/beams.min.svg", 2), "out/beams.min-2.svg");
+        assert_eq!(part_file("route", 2), "route-2");
+    }
+
+    #[test]
+    fn heat_colors() {
+        assert_eq!(heat_color(0, 9), "hsl(240, 80%, 25%)");
+        assert_eq!(heat_color(9, 9), "hsl(0, 80%, 50%)");
+        assert_eq!(heat_color(20, 9), "hsl(0, 80%, 50%)");
+        assert_eq!(heat_color(3, 0), "hsl(240, 80%, 25%)");
+    }
+}
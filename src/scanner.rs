@@ -0,0 +1,204 @@
+//! Finding many patterns at once in a single pass over the text, like day 1's spelled-out digits.
+//!
+//! ```
+//! use advent_of_code::scanner::Scanner;
+//!
+//! let scanner = Scanner::new([("one", 1), ("eight", 8), ("two", 2)]);
+//! let values = scanner.find_iter("eightwone").map(|m| *m.value).collect::<Vec<_>>();
+//! assert_eq!(values, [8, 2, 1]);
+//! let (first, last) = scanner.first_and_last("eightwone").unwrap();
+//! assert_eq!((first.start, last.start), (0, 6));
+//! ```
+
+use std::{
+    cmp::{self, Reverse},
+    collections::{HashMap, VecDeque},
+};
+
+/// A match of a pattern as byte offsets into the text.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Match<'s, T> {
+    pub start: usize,
+    pub end: usize,
+    /// The value the pattern was registered with.
+    pub value: &'s T,
+}
+
+// derived, these would require `T: Copy`
+impl<T> Clone for Match<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Match<'_, T> {}
+
+impl<T> Match<'_, T> {
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    next: HashMap<u8, usize>,
+    /// The node of the longest proper suffix that is also in the trie.
+    fail: usize,
+    /// The patterns ending here, longest first.
+    outputs: Vec<usize>,
+}
+
+/// An Aho-Corasick automaton over a set of patterns, each with a value.
+///
+/// Patterns are matched as bytes, so the offsets of a match are always char boundaries of the
+/// text. Empty patterns never match.
+#[derive(Debug, Clone)]
+pub struct Scanner<T> {
+    nodes: Vec<Node>,
+    /// The length and value of every pattern.
+    patterns: Vec<(usize, T)>,
+}
+
+impl<T> Scanner<T> {
+    pub fn new<S: AsRef<str>>(patterns: impl IntoIterator<Item = (S, T)>) -> Self {
+        let mut scanner = Self {
+            nodes: vec![Node::default()],
+            patterns: vec![],
+        };
+        for (pattern, value) in patterns {
+            scanner.insert(pattern.as_ref(), value);
+        }
+        scanner.link();
+        scanner
+    }
+
+    fn insert(&mut self, pattern: &str, value: T) {
+        if pattern.is_empty() {
+            return;
+        }
+        let mut node = 0;
+        for byte in pattern.bytes() {
+            node = match self.nodes[node].next.get(&byte) {
+                Some(&next) => next,
+                None => {
+                    self.nodes.push(Node::default());
+                    let next = self.nodes.len() - 1;
+                    self.nodes[node].next.insert(byte, next);
+                    next
+                }
+            };
+        }
+        self.nodes[node].outputs.push(self.patterns.len());
+        self.patterns.push((pattern.len(), value));
+    }
+
+    /// Sets the fail links breadth first, so the links of shorter prefixes are done first.
+    fn link(&mut self) {
+        let mut queue = self.nodes[0]
+            .next
+            .values()
+            .copied()
+            .collect::<VecDeque<_>>();
+        while let Some(node) = queue.pop_front() {
+            let edges = self.nodes[node]
+                .next
+                .iter()
+                .map(|(&byte, &next)| (byte, next))
+                .collect::<Vec<_>>();
+            for (byte, next) in edges {
+                // `node` is never the root, so this is a proper suffix of `next`
+                let fail = self.step(self.nodes[node].fail, byte);
+                self.nodes[next].fail = fail;
+                let inherited = self.nodes[fail].outputs.clone();
+                self.nodes[next].outputs.extend(inherited);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    fn step(&self, mut node: usize, byte: u8) -> usize {
+        loop {
+            if let Some(&next) = self.nodes[node].next.get(&byte) {
+                return next;
+            }
+            if node == 0 {
+                return 0;
+            }
+            node = self.nodes[node].fail;
+        }
+    }
+
+    /// All matches including overlapping ones, ordered by their end and the longer one first
+    /// for the same end.
+    pub fn find_iter<'s>(&'s self, text: &'s str) -> impl Iterator<Item = Match<'s, T>> + 's {
+        let mut node = 0;
+        text.bytes().enumerate().flat_map(move |(i, byte)| {
+            node = self.step(node, byte);
+            self.nodes[node].outputs.iter().map(move |&pattern| {
+                let (len, value) = &self.patterns[pattern];
+                Match {
+                    start: i + 1 - len,
+                    end: i + 1,
+                    value,
+                }
+            })
+        })
+    }
+
+    /// The leftmost and the rightmost match by their start, the longer one if several start at
+    /// the same offset.
+    pub fn first_and_last<'s>(&'s self, text: &'s str) -> Option<(Match<'s, T>, Match<'s, T>)> {
+        self.find_iter(text).fold(None, |found, m| match found {
+            None => Some((m, m)),
+            Some((first, last)) => Some((
+                cmp::min_by_key(first, m, |m| (m.start, Reverse(m.len()))),
+                cmp::min_by_key(last, m, |m| (Reverse(m.start), Reverse(m.len()))),
+            )),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values<'s, T: Copy + 's>(matches: impl Iterator<Item = Match<'s, T>>) -> Vec<T> {
+        matches.map(|m| *m.value).collect()
+    }
+
+    #[test]
+    fn overlapping() {
+        let scanner = Scanner::new([("he", 1), ("she", 2), ("his", 3), ("hers", 4)]);
+        let matches = scanner.find_iter("ushers").collect::<Vec<_>>();
+        assert_eq!(values(matches.iter().copied()), [2, 1, 4]);
+        assert_eq!((matches[0].start, matches[0].end), (1, 4));
+        assert_eq!((matches[2].start, matches[2].end), (2, 6));
+        assert_eq!(values(scanner.find_iter("ahishers")), [3, 2, 1, 4]);
+        assert_eq!(scanner.find_iter("xyz").count(), 0);
+    }
+
+    #[test]
+    fn first_and_last() {
+        let scanner = Scanner::new([("a", 'a'), ("abc", 'c'), ("b", 'b'), ("", 'e')]);
+        let (first, last) = scanner.first_and_last("xabcx").unwrap();
+        // both `a` and `abc` start first, the longer one wins
+        assert_eq!((*first.value, first.start), ('c', 1));
+        assert_eq!((*last.value, last.start), ('b', 2));
+        assert_eq!(scanner.first_and_last("xyz"), None);
+
+        let (first, last) = scanner.first_and_last("b").unwrap();
+        assert_eq!(first, last);
+    }
+
+    #[test]
+    fn unicode() {
+        let scanner = Scanner::new([("drei", 3), ("fünf", 5)]);
+        let m = scanner.find_iter("zweifünfdrei").next().unwrap();
+        assert_eq!(&"zweifünfdrei"[m.start..m.end], "fünf");
+        assert_eq!(values(scanner.find_iter("zweifünfdrei")), [5, 3]);
+    }
+}
@@ -36,6 +36,8 @@ pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
 /// Creates the constant `DAY` and sets up the input and runner for each part.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+/// Alternatively, `extras = <function>` runs `function(&input)` once after both parts. It is not
+/// timed, so it is the place for flags that print or write files.
 #[macro_export]
 macro_rules! solution {
     ($day:expr) => {
@@ -47,8 +49,11 @@ macro_rules! solution {
     ($day:expr, 2) => {
         $crate::solution!(@impl $day, [part_two, 2]);
     };
+    ($day:expr, extras = $extras:expr) => {
+        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2]; $extras);
+    };
 
-    (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
+    (@impl $day:expr, $( [$func:expr, $part:expr] )* $(; $extras:expr)?) => {
         /// The current day.
         const DAY: $crate::template::Day = $crate::day!($day);
 
@@ -56,6 +61,7 @@ macro_rules! solution {
             use $crate::template::runner::*;
             let input = $crate::template::read_file("inputs", DAY);
             $( run_part($func, &input, DAY, $part); )*
+            $( $extras(&input); )?
         }
     };
 }